@@ -0,0 +1,309 @@
+//! Allocation of ranges from a pool of free ranges, for example virtual addresses or ports.
+//! The free ranges are kept sorted and coalesced, allocating carves the requested range out of a free range,
+//! and freeing returns it back, merging it with its free neighbours.
+
+
+use std::cmp::Ordering;
+use std::ops::Bound;
+use crate::arithmetic::Arithmetic;
+use crate::bounds::cmp_cut;
+use crate::normalize::normalize;
+use crate::range_interval::RangeInterval;
+use crate::successor::Successor;
+
+/// The strategy to select the free range for an allocation.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum FitPolicy {
+    /// The first free range, from the lowest, which can hold the allocation.
+    FirstFit,
+    /// The smallest free range which can hold the allocation.
+    BestFit,
+    /// The first free range which can hold the allocation, searching from the end of the previous allocation.
+    NextFit,
+}
+
+/// Errors of the allocation and freeing of ranges.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum AllocError {
+    /// The size or the alignment is zero, or the range contains no value.
+    InvalidRequest,
+    /// There is no free range which can hold the allocation.
+    OutOfSpace,
+    /// The requested range is not free.
+    NotFree,
+    /// The whole freed range is already free.
+    DoubleFree,
+    /// A part of the freed range is already free.
+    OverlappingFree,
+    /// A part of the freed range is outside of the pool of the allocator.
+    OutsidePool,
+}
+
+/// Sorted disjoint free ranges with included start and excluded end, the neighbouring ranges are coalesced.
+#[derive(Debug, Clone)]
+pub(crate) struct FreeList<T> {
+    ranges: Vec<RangeInterval<T>>,
+}
+
+impl<T: PartialOrd + Successor + Clone> FreeList<T> {
+    pub(crate) fn new(ranges: impl IntoIterator<Item = RangeInterval<T>>) -> Self {
+        Self { ranges: normalize(ranges.into_iter().filter_map(|range| range.half_open())) }
+    }
+
+    pub(crate) fn ranges(&self) -> &[RangeInterval<T>] {
+        &self.ranges
+    }
+
+    /// Add the free range and coalesce it with its neighbours.
+    pub(crate) fn insert(&mut self, range: RangeInterval<T>) -> Result<(), AllocError> {
+        let range = RangeInterval { reverse: false, ..range }.half_open().ok_or(AllocError::InvalidRequest)?;
+        let index = self.ranges.partition_point(|r| r.cmp_bounds(&range) == Ordering::Less);
+        for neighbour in self.ranges[index.saturating_sub(1)..].iter().take(2) {
+            if neighbour.covers(&range) {
+                return Err(AllocError::DoubleFree);
+            }
+            if neighbour.overlaps(&range) {
+                return Err(AllocError::OverlappingFree);
+            }
+        }
+        self.ranges.insert(index, range);
+        if index + 1 < self.ranges.len() && self.ranges[index].connects(&self.ranges[index + 1]) {
+            let next = self.ranges.remove(index + 1);
+            self.ranges[index].end = next.end;
+        }
+        if index > 0 && self.ranges[index - 1].connects(&self.ranges[index]) {
+            let range = self.ranges.remove(index);
+            self.ranges[index - 1].end = range.end;
+        }
+        Ok(())
+    }
+
+    /// Remove the range from the free ranges, the whole range must be free.
+    pub(crate) fn remove(&mut self, range: RangeInterval<T>) -> Result<RangeInterval<T>, AllocError> {
+        let range = RangeInterval { reverse: false, ..range }.half_open().ok_or(AllocError::InvalidRequest)?;
+        let index = self.ranges.partition_point(|r| cmp_cut(&r.start_cut(), &range.start_cut()) != Ordering::Greater);
+        if index == 0 || !self.ranges[index - 1].covers(&range) {
+            return Err(AllocError::NotFree);
+        }
+        let free = self.ranges.remove(index - 1);
+        let parts = free.subtract(range.clone()).into_vec();
+        for (offset, part) in parts.into_iter().filter_map(|part| part.half_open()).enumerate() {
+            self.ranges.insert(index - 1 + offset, part);
+        }
+        Ok(range)
+    }
+}
+
+/// Allocator of ranges from a pool of free ranges.
+#[derive(Debug, Clone)]
+pub struct RangeAllocator<T> {
+    free: FreeList<T>,
+    /// The sorted disjoint ranges of the pool, the freed ranges must be inside them.
+    pool: Vec<RangeInterval<T>>,
+    policy: FitPolicy,
+    cursor: Option<T>,
+}
+
+impl<T: Arithmetic + PartialOrd + Successor + Clone> RangeAllocator<T> {
+    /// Create the allocator owning the pool of free ranges. Only the ranges with a start can be used by `allocate`.
+    pub fn new(pool: impl IntoIterator<Item = RangeInterval<T>>, policy: FitPolicy) -> Self {
+        let free = FreeList::new(pool);
+        let pool = free.ranges().to_vec();
+        Self { free, pool, policy, cursor: None }
+    }
+
+    /// The policy used to select the free range.
+    pub fn policy(&self) -> FitPolicy {
+        self.policy
+    }
+
+    /// The free ranges, sorted with included start and excluded end.
+    pub fn free_ranges(&self) -> &[RangeInterval<T>] {
+        self.free.ranges()
+    }
+
+    /// Allocate a range of the size, with the start aligned to a multiple of the alignment.
+    pub fn allocate(&mut self, size: T, align: T) -> Result<RangeInterval<T>, AllocError> {
+        let zero = T::zero();
+        if size <= zero || align <= zero {
+            return Err(AllocError::InvalidRequest);
+        }
+        let ranges = self.free.ranges();
+        let found = match self.policy {
+            FitPolicy::FirstFit => ranges.iter().find_map(|free| fit(free, None, &size, &align)),
+            FitPolicy::BestFit => ranges.iter()
+                .filter_map(|free| fit(free, None, &size, &align).map(|range| (span(free), range)))
+                .fold(None, |best: Option<(Option<T>, RangeInterval<T>)>, (span, range)| match best {
+                    Some((best_span, best_range)) if !is_smaller(&span, &best_span) => Some((best_span, best_range)),
+                    _ => Some((span, range)),
+                })
+                .map(|(_, range)| range),
+            FitPolicy::NextFit => match &self.cursor {
+                None => ranges.iter().find_map(|free| fit(free, None, &size, &align)),
+                Some(cursor) => {
+                    let index = ranges.partition_point(|free| match &free.end {
+                        Bound::Included(end) => end < cursor,
+                        Bound::Excluded(end) => end <= cursor,
+                        Bound::Unbounded => false,
+                    });
+                    ranges[index..].iter().find_map(|free| fit(free, Some(cursor), &size, &align))
+                        .or_else(|| ranges[..(index + 1).min(ranges.len())].iter().find_map(|free| fit(free, None, &size, &align)))
+                }
+            },
+        };
+        let range = found.ok_or(AllocError::OutOfSpace)?;
+        let range = self.free.remove(range)?;
+        self.cursor = match &range.end {
+            Bound::Excluded(end) => Some(end.clone()),
+            _ => None,
+        };
+        Ok(range)
+    }
+
+    /// Allocate the exact range, the whole range must be free. The reversed range is allocated forward.
+    pub fn allocate_at(&mut self, range: RangeInterval<T>) -> Result<RangeInterval<T>, AllocError> {
+        self.free.remove(range)
+    }
+
+    /// Return the range to the free ranges, coalescing it with the free neighbours. The whole range must be
+    /// inside the pool.
+    pub fn free(&mut self, range: RangeInterval<T>) -> Result<(), AllocError> {
+        let range = RangeInterval { reverse: false, ..range }.half_open().ok_or(AllocError::InvalidRequest)?;
+        let index = self.pool.partition_point(|pool| cmp_cut(&pool.start_cut(), &range.start_cut()) != Ordering::Greater);
+        if index == 0 || !self.pool[index - 1].covers(&range) {
+            return Err(AllocError::OutsidePool);
+        }
+        self.free.insert(range)
+    }
+}
+
+/// Find the aligned range of the size in the free range, starting not before `from`.
+fn fit<T: Arithmetic + PartialOrd + Successor + Clone>(free: &RangeInterval<T>, from: Option<&T>, size: &T, align: &T) -> Option<RangeInterval<T>> {
+    let start = match &free.start {
+        Bound::Included(start) => start,
+        _ => return None,
+    };
+    let start = match from {
+        Some(from) if from > start => from,
+        _ => start,
+    };
+    let mut aligned = start.try_div(align)?.try_mul(align)?;
+    if aligned < *start {
+        aligned = aligned.try_add(align)?;
+    }
+    let last = aligned.try_add(&size.try_sub(&T::zero().next()?)?)?;
+    let fits = match &free.end {
+        Bound::Included(end) => last <= *end,
+        Bound::Excluded(end) => last < *end,
+        Bound::Unbounded => true,
+    };
+    if fits {
+        Some(RangeInterval { reverse: false, start: Bound::Included(aligned), end: Bound::Included(last) })
+    } else {
+        None
+    }
+}
+
+/// The distance between the first and the last value of the free range, None if it is unbounded.
+fn span<T: Arithmetic + Successor + Clone>(free: &RangeInterval<T>) -> Option<T> {
+    let last = match &free.end {
+        Bound::Included(end) => end.clone(),
+        Bound::Excluded(end) => end.prev()?,
+        Bound::Unbounded => return None,
+    };
+    match &free.start {
+        Bound::Included(start) => last.try_sub(start),
+        _ => None,
+    }
+}
+
+fn is_smaller<T: PartialOrd>(a: &Option<T>, b: &Option<T>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a < b,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_first_fit() {
+        let mut allocator = RangeAllocator::new(vec![r!(0u64..100)], FitPolicy::FirstFit);
+        assert_eq!(allocator.allocate(10, 1), Ok(r!(0..10)));
+        assert_eq!(allocator.allocate(10, 16), Ok(r!(16..26)));
+        assert_eq!(allocator.free_ranges(), &[r!(10..16), r!(26..100)]);
+        assert_eq!(allocator.allocate(6, 2), Ok(r!(10..16)));
+        assert_eq!(allocator.allocate(75, 1), Err(AllocError::OutOfSpace));
+        assert_eq!(allocator.allocate(74, 1), Ok(r!(26..100)));
+        assert_eq!(allocator.free_ranges(), &[]);
+        assert_eq!(allocator.allocate(1, 1), Err(AllocError::OutOfSpace));
+        assert_eq!(allocator.allocate(0, 1), Err(AllocError::InvalidRequest));
+        assert_eq!(allocator.allocate(1, 0), Err(AllocError::InvalidRequest));
+    }
+
+    #[test]
+    fn test_best_fit() {
+        let mut allocator = RangeAllocator::new(vec![r!(0u64..50), r!(60..70), r!(80..=84), r!(90..)], FitPolicy::BestFit);
+        assert_eq!(allocator.allocate(4, 1), Ok(r!(80..84)));
+        assert_eq!(allocator.allocate(8, 1), Ok(r!(60..68)));
+        assert_eq!(allocator.allocate(30, 1), Ok(r!(0..30)));
+        assert_eq!(allocator.allocate(30, 1), Ok(r!(90..120)));
+        assert_eq!(allocator.free_ranges(), &[r!(30..50), r!(68..70), r!(84..85), r!(120..)]);
+    }
+
+    #[test]
+    fn test_next_fit() {
+        let mut allocator = RangeAllocator::new(vec![r!(0u64..30)], FitPolicy::NextFit);
+        assert_eq!(allocator.allocate(10, 1), Ok(r!(0..10)));
+        assert_eq!(allocator.free(r!(0..10)), Ok(()));
+        assert_eq!(allocator.allocate(10, 1), Ok(r!(10..20)));
+        assert_eq!(allocator.allocate(5, 1), Ok(r!(20..25)));
+        assert_eq!(allocator.allocate(10, 1), Ok(r!(0..10)));
+        assert_eq!(allocator.allocate(5, 1), Ok(r!(25..30)));
+        assert_eq!(allocator.allocate(1, 1), Err(AllocError::OutOfSpace));
+    }
+
+    #[test]
+    fn test_allocate_at() {
+        let mut allocator = RangeAllocator::new(vec![r!(0u16..=0xffff)], FitPolicy::FirstFit);
+        assert_eq!(allocator.allocate_at(r!(80..=80)), Ok(r!(80..81)));
+        assert_eq!(allocator.allocate_at(r!(!442..=443)), Ok(r!(443..444)));
+        assert_eq!(allocator.allocate_at(r!(70..90)), Err(AllocError::NotFree));
+        assert_eq!(allocator.allocate_at(r!(!1..2)), Err(AllocError::InvalidRequest));
+        assert_eq!(allocator.allocate_at(r!(0xff00..=0xffff)), Ok(r!(0xff00..=0xffff)));
+        assert_eq!(allocator.free_ranges(), &[r!(0..80), r!(81..443), r!(444..0xff00)]);
+        assert_eq!(allocator.allocate(0x100, 0x100), Ok(r!(0x200..0x300)));
+        assert_eq!(allocator.allocate_at(r!(20..10)), Ok(r!(!10..=20).half_open().unwrap()));
+        assert_eq!(allocator.free_ranges()[0..2], [r!(0..11), r!(21..80)]);
+        assert_eq!(allocator.free(r!(20..10)), Ok(()));
+        assert_eq!(allocator.free_ranges()[0], r!(0..80));
+    }
+
+    #[test]
+    fn test_free() {
+        let mut allocator = RangeAllocator::new(vec![r!(0u32..100)], FitPolicy::FirstFit);
+        let a = allocator.allocate(10, 1).unwrap();
+        let b = allocator.allocate(10, 1).unwrap();
+        let c = allocator.allocate(10, 1).unwrap();
+        assert_eq!(allocator.free_ranges(), &[r!(30..100)]);
+        assert_eq!(allocator.free(b), Ok(()));
+        assert_eq!(allocator.free_ranges(), &[r!(10..20), r!(30..100)]);
+        assert_eq!(allocator.free(b), Err(AllocError::DoubleFree));
+        assert_eq!(allocator.free(r!(15..25)), Err(AllocError::OverlappingFree));
+        assert_eq!(allocator.free(r!(5..=10)), Err(AllocError::OverlappingFree));
+        assert_eq!(allocator.free(r!(0..50)), Err(AllocError::OverlappingFree));
+        assert_eq!(allocator.free(a), Ok(()));
+        assert_eq!(allocator.free_ranges(), &[r!(0..20), r!(30..100)]);
+        assert_eq!(allocator.free(r!(20..=29)), Ok(()));
+        assert_eq!(allocator.free_ranges(), &[r!(0..100)]);
+        assert_eq!(allocator.free(c), Err(AllocError::DoubleFree));
+        assert_eq!(allocator.free(r!(500..600)), Err(AllocError::OutsidePool));
+        assert_eq!(allocator.free(r!(90..=100)), Err(AllocError::OutsidePool));
+        assert_eq!(allocator.free_ranges(), &[r!(0..100)]);
+    }
+}
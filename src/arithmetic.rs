@@ -0,0 +1,588 @@
+//! Interval arithmetic on ranges. The result of an operation between two ranges is the range containing the
+//! results of the operation for all the values from both ranges, `<a..b> + <c..d>` is `<a+c..b+d>`.
+//!
+//! An end of the result is excluded when it is calculated from an excluded end, and unbounded ends act as the
+//! infinity. An operation with a void range has no value, so its result is void. The integer ranges are divided
+//! with their excluded ends replaced by the included neighbours, as the truncating division can round an excluded
+//! end onto a value which is in the result, and the excluded zero of a divisor is not approached by the values
+//! between. The operators on ranges
+//! are reserved for the set operations, so the arithmetic is available through the methods `add_interval`,
+//! `sub_interval`, `mul_interval` and `div_interval`, and their checked variants which detect an overflow.
+
+
+use std::cmp::Ordering;
+use std::ops::{Add, Bound, Div, Mul, Sub};
+use crate::binary_result::BinaryResult;
+use crate::bounds::cmp_cut;
+use crate::range_interval::RangeInterval;
+
+/// A trait that represents numerical operations needed for the interval arithmetic.
+pub trait Arithmetic: Sized {
+    /// The zero value
+    fn zero() -> Self;
+
+    /// Addition, None if the result overflows
+    fn try_add(&self, other: &Self) -> Option<Self>;
+
+    /// Subtraction, None if the result overflows
+    fn try_sub(&self, other: &Self) -> Option<Self>;
+
+    /// Multiplication, None if the result overflows
+    fn try_mul(&self, other: &Self) -> Option<Self>;
+
+    /// Division, None if the result overflows or the other is zero
+    fn try_div(&self, other: &Self) -> Option<Self>;
+
+    /// The distance between the neighbouring values of the discrete types, None for the continuous types
+    fn step() -> Option<Self> {
+        None
+    }
+}
+
+
+macro_rules! impl_int {
+    ($t: ty) => {
+        impl Arithmetic for $t {
+            fn zero() -> Self {
+                0
+            }
+            fn try_add(&self, other: &Self) -> Option<Self> {
+                self.checked_add(*other)
+            }
+            fn try_sub(&self, other: &Self) -> Option<Self> {
+                self.checked_sub(*other)
+            }
+            fn try_mul(&self, other: &Self) -> Option<Self> {
+                self.checked_mul(*other)
+            }
+            fn try_div(&self, other: &Self) -> Option<Self> {
+                self.checked_div(*other)
+            }
+            fn step() -> Option<Self> {
+                Some(1)
+            }
+        }
+    }
+}
+
+macro_rules! impl_float {
+    ($t: ty) => {
+        impl Arithmetic for $t {
+            fn zero() -> Self {
+                0.0
+            }
+            fn try_add(&self, other: &Self) -> Option<Self> {
+                Some(self + other)
+            }
+            fn try_sub(&self, other: &Self) -> Option<Self> {
+                Some(self - other)
+            }
+            fn try_mul(&self, other: &Self) -> Option<Self> {
+                Some(self * other)
+            }
+            fn try_div(&self, other: &Self) -> Option<Self> {
+                Some(self / other)
+            }
+        }
+    }
+}
+
+impl_int!(i8);
+impl_int!(i16);
+impl_int!(i32);
+impl_int!(i64);
+impl_int!(isize);
+impl_int!(u8);
+impl_int!(u16);
+impl_int!(u32);
+impl_int!(u64);
+impl_int!(usize);
+impl_float!(f32);
+impl_float!(f64);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// Errors of the interval division.
+pub enum ArithmeticError {
+    /// The divisor contains only the zero.
+    DivisionByZero,
+    /// Calculation of an end of the result overflows.
+    Overflow,
+}
+
+/// Operations on the values, either checked or plain.
+struct Ops<T> {
+    add: fn(&T, &T) -> Option<T>,
+    sub: fn(&T, &T) -> Option<T>,
+    mul: fn(&T, &T) -> Option<T>,
+    div: fn(&T, &T) -> Option<T>,
+}
+
+impl<T: Arithmetic> Ops<T> {
+    fn checked() -> Self {
+        Ops { add: T::try_add, sub: T::try_sub, mul: T::try_mul, div: T::try_div }
+    }
+}
+
+impl<T: Clone + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>> Ops<T> {
+    fn plain() -> Self {
+        Ops {
+            add: |a, b| Some(a.clone() + b.clone()),
+            sub: |a, b| Some(a.clone() - b.clone()),
+            mul: |a, b| Some(a.clone() * b.clone()),
+            div: |a, b| Some(a.clone() / b.clone()),
+        }
+    }
+}
+
+/// An end of a range on the extended number line, the value is with the flag if it is included.
+#[derive(Clone)]
+enum Ext<T> {
+    NegInf,
+    Value(T, bool),
+    PosInf,
+}
+
+impl<T: Arithmetic + PartialOrd> Ext<T> {
+    fn lower(bound: Bound<T>) -> Self {
+        match bound {
+            Bound::Included(v) => Ext::Value(v, true),
+            Bound::Excluded(v) => Ext::Value(v, false),
+            Bound::Unbounded => Ext::NegInf,
+        }
+    }
+
+    fn upper(bound: Bound<T>) -> Self {
+        match bound {
+            Bound::Included(v) => Ext::Value(v, true),
+            Bound::Excluded(v) => Ext::Value(v, false),
+            Bound::Unbounded => Ext::PosInf,
+        }
+    }
+
+    fn into_bound(self) -> Bound<T> {
+        match self {
+            Ext::Value(v, true) => Bound::Included(v),
+            Ext::Value(v, false) => Bound::Excluded(v),
+            Ext::NegInf | Ext::PosInf => Bound::Unbounded,
+        }
+    }
+
+    fn infinity(sign: Ordering) -> Self {
+        match sign {
+            Ordering::Less => Ext::NegInf,
+            Ordering::Greater => Ext::PosInf,
+            Ordering::Equal => Ext::Value(T::zero(), true),
+        }
+    }
+
+    /// The sign of the end compared to zero.
+    fn sign(&self) -> Ordering {
+        match self {
+            Ext::NegInf => Ordering::Less,
+            Ext::Value(v, _) => sign(v),
+            Ext::PosInf => Ordering::Greater,
+        }
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Ext::Value(a, _), Ext::Value(b, _)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Ext::NegInf => 0,
+            Ext::Value(_, _) => 1,
+            Ext::PosInf => 2,
+        }
+    }
+
+    fn is_included(&self) -> bool {
+        matches!(self, Ext::Value(_, true))
+    }
+
+    /// The product of two ends, an included zero makes the product an included zero.
+    fn product(&self, other: &Self, ops: &Ops<T>) -> Option<Self> {
+        Some(match (self, other) {
+            (Ext::Value(a, ia), Ext::Value(b, ib)) => {
+                let included = (*ia && (*ib || sign(a) == Ordering::Equal)) || (*ib && sign(b) == Ordering::Equal);
+                Ext::Value((ops.mul)(a, b)?, included)
+            }
+            (Ext::Value(v, included), _) | (_, Ext::Value(v, included)) if sign(v) == Ordering::Equal => {
+                Ext::Value(T::zero(), *included)
+            }
+            _ => Ext::infinity(mul_sign(self.sign(), other.sign())),
+        })
+    }
+
+    /// The quotient of two ends, the divisor does not contain zero, but its end can be an excluded zero,
+    /// in that case the quotient is the infinity with the sign given by the side of the divisor. A value divided
+    /// by the infinity is zero, which the truncating division of the discrete types reaches.
+    fn quotient(&self, other: &Self, divisor_sign: Ordering, ops: &Ops<T>) -> Option<Self> {
+        Some(match (self, other) {
+            (Ext::Value(a, ia), Ext::Value(b, ib)) => {
+                if sign(a) == Ordering::Equal {
+                    Ext::Value(T::zero(), *ia)
+                } else if sign(b) == Ordering::Equal {
+                    Ext::infinity(mul_sign(sign(a), divisor_sign))
+                } else {
+                    Ext::Value((ops.div)(a, b)?, *ia && *ib)
+                }
+            }
+            (Ext::Value(a, ia), _) => Ext::Value(T::zero(), *ia && (sign(a) == Ordering::Equal || T::step().is_some())),
+            (_, Ext::Value(b, _)) if sign(b) == Ordering::Equal => Ext::infinity(mul_sign(self.sign(), divisor_sign)),
+            _ => Ext::infinity(mul_sign(self.sign(), other.sign())),
+        })
+    }
+}
+
+fn sign<T: Arithmetic + PartialOrd>(value: &T) -> Ordering {
+    value.partial_cmp(&T::zero()).unwrap_or(Ordering::Equal)
+}
+
+fn mul_sign(a: Ordering, b: Ordering) -> Ordering {
+    match (a, b) {
+        (Ordering::Equal, _) | (_, Ordering::Equal) => Ordering::Equal,
+        _ if a == b => Ordering::Greater,
+        _ => Ordering::Less,
+    }
+}
+
+/// Select the lowest and the highest from the candidates, preferring the included ones.
+fn select<T: Arithmetic + PartialOrd + Clone>(candidates: Vec<Ext<T>>) -> (Ext<T>, Ext<T>) {
+    let mut lower: Option<Ext<T>> = None;
+    let mut upper: Option<Ext<T>> = None;
+    for candidate in candidates {
+        let replace_lower = match &lower {
+            None => true,
+            Some(l) => match candidate.cmp(l) {
+                Ordering::Less => true,
+                Ordering::Equal => candidate.is_included(),
+                Ordering::Greater => false,
+            }
+        };
+        let replace_upper = match &upper {
+            None => true,
+            Some(u) => match candidate.cmp(u) {
+                Ordering::Greater => true,
+                Ordering::Equal => candidate.is_included(),
+                Ordering::Less => false,
+            }
+        };
+        if replace_lower && replace_upper {
+            lower = Some(candidate.clone());
+            upper = Some(candidate);
+        } else if replace_lower {
+            lower = Some(candidate);
+        } else if replace_upper {
+            upper = Some(candidate);
+        }
+    }
+    (lower.unwrap_or(Ext::NegInf), upper.unwrap_or(Ext::PosInf))
+}
+
+impl<T: Arithmetic + PartialOrd + Clone> RangeInterval<T> {
+    fn from_ends(reverse: bool, lower: Ext<T>, upper: Ext<T>) -> Self {
+        let start = lower.into_bound();
+        let end = upper.into_bound();
+        let reverse = reverse && !matches!((&start, &end), (Bound::Unbounded, _) | (_, Bound::Unbounded));
+        RangeInterval { reverse, start, end }
+    }
+
+    fn ends(self) -> (Ext<T>, Ext<T>) {
+        (Ext::lower(self.start), Ext::upper(self.end))
+    }
+
+    /// The void operand, the operation with it has no value, so its result is void as well.
+    fn void_operand(&self, other: &Self) -> Option<Self> {
+        [self, other].iter().find(|range| range.is_void()).map(|&range| RangeInterval { reverse: false, ..range.clone() })
+    }
+
+    fn add_with(self, other: Self, ops: &Ops<T>) -> Option<Self> {
+        if let Some(void) = self.void_operand(&other) {
+            return Some(void);
+        }
+        let reverse = self.reverse;
+        let lower = match (Ext::lower(self.start), Ext::lower(other.start)) {
+            (Ext::Value(a, ia), Ext::Value(b, ib)) => Ext::Value((ops.add)(&a, &b)?, ia && ib),
+            _ => Ext::NegInf,
+        };
+        let upper = match (Ext::upper(self.end), Ext::upper(other.end)) {
+            (Ext::Value(a, ia), Ext::Value(b, ib)) => Ext::Value((ops.add)(&a, &b)?, ia && ib),
+            _ => Ext::PosInf,
+        };
+        Some(Self::from_ends(reverse, lower, upper))
+    }
+
+    fn sub_with(self, other: Self, ops: &Ops<T>) -> Option<Self> {
+        if let Some(void) = self.void_operand(&other) {
+            return Some(void);
+        }
+        let reverse = self.reverse;
+        let lower = match (Ext::lower(self.start), Ext::upper(other.end)) {
+            (Ext::Value(a, ia), Ext::Value(b, ib)) => Ext::Value((ops.sub)(&a, &b)?, ia && ib),
+            _ => Ext::NegInf,
+        };
+        let upper = match (Ext::upper(self.end), Ext::lower(other.start)) {
+            (Ext::Value(a, ia), Ext::Value(b, ib)) => Ext::Value((ops.sub)(&a, &b)?, ia && ib),
+            _ => Ext::PosInf,
+        };
+        Some(Self::from_ends(reverse, lower, upper))
+    }
+
+    fn mul_with(self, other: Self, ops: &Ops<T>) -> Option<Self> {
+        if let Some(void) = self.void_operand(&other) {
+            return Some(void);
+        }
+        let reverse = self.reverse;
+        let (a, b) = self.ends();
+        let (c, d) = other.ends();
+        let (lower, upper) = select(vec![
+            a.product(&c, ops)?,
+            a.product(&d, ops)?,
+            b.product(&c, ops)?,
+            b.product(&d, ops)?,
+        ]);
+        Some(Self::from_ends(reverse, lower, upper))
+    }
+
+    /// Division by a divisor which does not contain zero and lies on the side of zero given by the sign.
+    fn div_part(self, other: Self, divisor_sign: Ordering, ops: &Ops<T>) -> Option<Self> {
+        let reverse = self.reverse;
+        let (a, b) = self.ends();
+        let (c, d) = other.ends();
+        let (lower, upper) = select(vec![
+            a.quotient(&c, divisor_sign, ops)?,
+            a.quotient(&d, divisor_sign, ops)?,
+            b.quotient(&c, divisor_sign, ops)?,
+            b.quotient(&d, divisor_sign, ops)?,
+        ]);
+        Some(Self::from_ends(reverse, lower, upper))
+    }
+
+    /// The range with the excluded ends replaced by the included neighbours, for the discrete types.
+    fn close_discrete(self) -> Self {
+        let step = match T::step() {
+            Some(step) => step,
+            None => return self,
+        };
+        let start = match self.start {
+            Bound::Excluded(v) => match v.try_add(&step) {
+                Some(next) => Bound::Included(next),
+                None => Bound::Excluded(v),
+            },
+            start => start,
+        };
+        let end = match self.end {
+            Bound::Excluded(v) => match v.try_sub(&step) {
+                Some(previous) => Bound::Included(previous),
+                None => Bound::Excluded(v),
+            },
+            end => end,
+        };
+        RangeInterval { reverse: self.reverse, start, end }
+    }
+
+    fn div_with(self, other: Self, ops: &Ops<T>) -> Result<BinaryResult<T>, ArithmeticError> {
+        self.close_discrete().div_closed(other.close_discrete(), ops)
+    }
+
+    fn div_closed(self, other: Self, ops: &Ops<T>) -> Result<BinaryResult<T>, ArithmeticError> {
+        if self.is_void() || other.is_void() {
+            return Ok(BinaryResult::None);
+        }
+        let negative = RangeInterval { reverse: false, start: other.start.clone(), end: Bound::Excluded(T::zero()) };
+        let positive = RangeInterval { reverse: false, start: Bound::Excluded(T::zero()), end: other.end.clone() };
+        if !other.contains(T::zero()) {
+            let divisor_sign = if positive.is_void() { Ordering::Less } else { Ordering::Greater };
+            return self.div_part(other, divisor_sign, ops).map(BinaryResult::One).ok_or(ArithmeticError::Overflow);
+        }
+        let parts = match (negative.is_void(), positive.is_void()) {
+            (true, true) => return Err(ArithmeticError::DivisionByZero),
+            (false, true) => vec![(negative, Ordering::Less)],
+            (true, false) => vec![(positive, Ordering::Greater)],
+            (false, false) => vec![(negative, Ordering::Less), (positive, Ordering::Greater)],
+        };
+        if self.contains(T::zero()) {
+            let only_zero = matches!((&self.start, &self.end),
+                (Bound::Included(a), Bound::Included(b)) if sign(a) == Ordering::Equal && sign(b) == Ordering::Equal);
+            if only_zero {
+                // zero divided by anything which is not zero
+                return Ok(BinaryResult::One(self));
+            }
+            return Ok(BinaryResult::One(RangeInterval { reverse: false, start: Bound::Unbounded, end: Bound::Unbounded }));
+        }
+        let mut results = Vec::with_capacity(2);
+        for (part, divisor_sign) in parts {
+            results.push(self.clone().div_part(part, divisor_sign, ops).ok_or(ArithmeticError::Overflow)?);
+        }
+        let second = results.pop();
+        let first = results.pop();
+        Ok(match (first, second) {
+            (Some(first), Some(second)) => {
+                // the negative divisor yields the upper part when self is positive
+                let (lower, upper) = if cmp_cut(&first.start_cut(), &second.start_cut()) != Ordering::Greater {
+                    (first, second)
+                } else {
+                    (second, first)
+                };
+                match lower.clone().gap(upper.clone()) {
+                    Some(_) => BinaryResult::Two(lower, upper),
+                    None => BinaryResult::One(lower.hull(upper)),
+                }
+            }
+            (None, Some(one)) | (Some(one), None) => BinaryResult::One(one),
+            (None, None) => BinaryResult::None,
+        })
+    }
+
+    /// Add two ranges, return None if an end overflows.
+    pub fn checked_add_interval(self, other: Self) -> Option<Self> {
+        self.add_with(other, &Ops::checked())
+    }
+
+    /// Subtract two ranges, `<a..b> - <c..d>` is `<a-d..b-c>`, return None if an end overflows.
+    pub fn checked_sub_interval(self, other: Self) -> Option<Self> {
+        self.sub_with(other, &Ops::checked())
+    }
+
+    /// Multiply two ranges, the ends are the lowest and the highest product of the ends,
+    /// return None if an end overflows.
+    pub fn checked_mul_interval(self, other: Self) -> Option<Self> {
+        self.mul_with(other, &Ops::checked())
+    }
+
+    /// Divide two ranges. If the divisor contains zero, it is split around the zero, which can yield two ranges.
+    /// Return an error if the divisor is only the zero, or an end overflows.
+    pub fn checked_div_interval(self, other: Self) -> Result<BinaryResult<T>, ArithmeticError> {
+        self.div_with(other, &Ops::checked())
+    }
+}
+
+impl<T> RangeInterval<T>
+    where T: Arithmetic + PartialOrd + Clone + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>
+{
+    /// Add two ranges, `<a..b> + <c..d>` is `<a+c..b+d>`.
+    pub fn add_interval(self, other: Self) -> Self {
+        match self.add_with(other, &Ops::plain()) {
+            Some(range) => range,
+            None => unreachable!("plain operations do not fail"),
+        }
+    }
+
+    /// Subtract two ranges, `<a..b> - <c..d>` is `<a-d..b-c>`.
+    pub fn sub_interval(self, other: Self) -> Self {
+        match self.sub_with(other, &Ops::plain()) {
+            Some(range) => range,
+            None => unreachable!("plain operations do not fail"),
+        }
+    }
+
+    /// Multiply two ranges, the ends are the lowest and the highest product of the ends.
+    pub fn mul_interval(self, other: Self) -> Self {
+        match self.mul_with(other, &Ops::plain()) {
+            Some(range) => range,
+            None => unreachable!("plain operations do not fail"),
+        }
+    }
+
+    /// Divide two ranges. If the divisor contains zero, it is split around the zero, which can yield two ranges.
+    /// Return an error if the divisor is only the zero.
+    pub fn div_interval(self, other: Self) -> Result<BinaryResult<T>, ArithmeticError> {
+        self.div_with(other, &Ops::plain())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_add() {
+        assert_eq!(r!(1..=3).add_interval(r!(7..=9)), r!(8..=12));
+        assert_eq!(r!(1..3).add_interval(r!(7..=9)), r!(8..12));
+        assert_eq!(r!(!1..3).add_interval(r!(7..=9)), r!(!8..12));
+        assert_eq!(r!(1..).add_interval(r!(7..=9)), r!(8..));
+        assert_eq!(r!(1..).add_interval(r!(..=9)), r!(..));
+        assert_eq!(r!(-2.5..=1.0).add_interval(r!(!0.5..=1.0)), r!(!-2.0..=2.0));
+    }
+
+    #[test]
+    fn test_void() {
+        assert!(r!(5..5).add_interval(r!(1..2)).is_void());
+        assert!(r!(1..2).add_interval(r!(!5..=5)).is_void());
+        assert!(r!(5..5).sub_interval(r!(1..=2)).is_void());
+        assert!(r!(..).sub_interval(r!(3..3)).is_void());
+        assert!(r!(5..5).mul_interval(r!(1..=2)).is_void());
+        assert!(r!(-1..=1).mul_interval(r!(0..0)).is_void());
+        assert_eq!(r!(100i8..100).checked_add_interval(r!(100..=100)), Some(r!(100..100)));
+    }
+
+    #[test]
+    fn test_sub() {
+        assert_eq!(r!(1..=3).sub_interval(r!(7..=9)), r!(-8..=-4));
+        assert_eq!(r!(1..=3).sub_interval(r!(7..9)), r!(!-8..=-4));
+        assert_eq!(r!(1..=3).sub_interval(r!(7..)), r!(..=-4));
+        assert_eq!(r!(..=3).sub_interval(r!(..=7)), r!(..));
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(r!(-2..=3).mul_interval(r!(4..=5)), r!(-10..=15));
+        assert_eq!(r!(-2..3).mul_interval(r!(4..=5)), r!(-10..15));
+        assert_eq!(r!(-2..=-1).mul_interval(r!(-3..=-2)), r!(2..=6));
+        assert_eq!(r!(0..=1).mul_interval(r!(5..)), r!(0..));
+        assert_eq!(r!(!0..=1).mul_interval(r!(5..)), r!(!0..));
+        assert_eq!(r!(0..=1).mul_interval(r!(!2..3)), r!(0..3));
+        assert_eq!(r!(-1..1).mul_interval(r!(-1..=1)), r!(-1..=1));
+        assert_eq!(r!(-1..=1).mul_interval(r!(..)), r!(..));
+    }
+
+    #[test]
+    fn test_div() {
+        assert_eq!(r!(1.0..=2.0).div_interval(r!(4.0..=8.0)), Ok(BinaryResult::One(r!(0.125..=0.5))));
+        assert_eq!(r!(1.0..=2.0).div_interval(r!(-8.0..=-4.0)), Ok(BinaryResult::One(r!(-0.5..=-0.125))));
+        assert_eq!(r!(1.0..=2.0).div_interval(r!(4.0..)), Ok(BinaryResult::One(r!(!0.0..=0.5))));
+        assert_eq!(r!(1.0..=2.0).div_interval(r!(!0.0..=4.0)), Ok(BinaryResult::One(r!(0.25..))));
+        assert_eq!(r!(1.0..=2.0).div_interval(r!(0.0..=4.0)), Ok(BinaryResult::One(r!(0.25..))));
+        assert_eq!(r!(1.0..=2.0).div_interval(r!(-4.0..=0.0)), Ok(BinaryResult::One(r!(..=-0.25))));
+        assert_eq!(
+            r!(1.0..=2.0).div_interval(r!(-1.0..=1.0)),
+            Ok(BinaryResult::Two(r!(..=-1.0), r!(1.0..)))
+        );
+        assert_eq!(
+            r!(-2.0..=-1.0).div_interval(r!(-1.0..=1.0)),
+            Ok(BinaryResult::Two(r!(..=-1.0), r!(1.0..)))
+        );
+        assert_eq!(
+            r!(1.0..=2.0).div_interval(r!(..)),
+            Ok(BinaryResult::Two(r!(..0.0), r!(!0.0..)))
+        );
+        assert_eq!(r!(-1.0..=2.0).div_interval(r!(-1.0..=1.0)), Ok(BinaryResult::One(r!(..))));
+        assert_eq!(r!(0.0..=0.0).div_interval(r!(-1.0..=1.0)), Ok(BinaryResult::One(r!(0.0..=0.0))));
+        assert_eq!(r!(1.0..=2.0).div_interval(r!(0.0..=0.0)), Err(ArithmeticError::DivisionByZero));
+        assert_eq!(r!(8..=16).div_interval(r!(2..=4)), Ok(BinaryResult::One(r!(2..=8))));
+        assert_eq!(r!(8..=16).div_interval(r!(2..2)), Ok(BinaryResult::None));
+        assert_eq!(r!(1..11).div_interval(r!(2..=2)), Ok(BinaryResult::One(r!(0..=5))));
+        assert_eq!(r!(1..=10).div_interval(r!(!0..=3)), Ok(BinaryResult::One(r!(0..=10))));
+        assert_eq!(r!(1..=10).div_interval(r!(-3..0)), Ok(BinaryResult::One(r!(-10..=0))));
+        assert_eq!(r!(5..=5).div_interval(r!(1..)), Ok(BinaryResult::One(r!(0..=5))));
+        assert_eq!(r!(!1..2).div_interval(r!(1..=2)), Ok(BinaryResult::None));
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(r!(100i8..=120).checked_add_interval(r!(1..=7)), Some(r!(101..=127)));
+        assert_eq!(r!(100i8..=120).checked_add_interval(r!(1..=8)), None);
+        assert_eq!(r!(1u8..=3).checked_sub_interval(r!(1..=1)), Some(r!(0..=2)));
+        assert_eq!(r!(1u8..=3).checked_sub_interval(r!(1..=2)), None);
+        assert_eq!(r!(-20i8..=10).checked_mul_interval(r!(2..=6)), Some(r!(-120..=60)));
+        assert_eq!(r!(-20i8..=10).checked_mul_interval(r!(2..=7)), None);
+        assert_eq!(r!(-128i8..=-64).checked_div_interval(r!(-1..=-1)), Err(ArithmeticError::Overflow));
+        assert_eq!(r!(-128i8..=-64).checked_div_interval(r!(1..=2)), Ok(BinaryResult::One(r!(-128..=-32))));
+    }
+}
@@ -12,4 +12,15 @@ pub enum BinaryResult<T> {
     One(RangeInterval<T>),
     /// The result is two ranges
     Two(RangeInterval<T>, RangeInterval<T>),
-}
\ No newline at end of file
+}
+
+impl<T> BinaryResult<T> {
+    /// The ranges of the result.
+    pub fn into_vec(self) -> Vec<RangeInterval<T>> {
+        match self {
+            BinaryResult::None => vec![],
+            BinaryResult::One(a) => vec![a],
+            BinaryResult::Two(a, b) => vec![a, b],
+        }
+    }
+}
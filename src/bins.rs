@@ -0,0 +1,227 @@
+//! Histogram bins, a partition of the values into ranges with the counts of the values in every range.
+//! The edges of the bins are exact, so the bins `<0..10)`, `<10..20)`, `<20..30>` put 10 in the second bin
+//! and 30 in the last one. The bins must not overlap and must not leave gaps between them.
+
+
+use std::cmp::Ordering;
+use std::ops::Bound;
+use crate::arithmetic::Arithmetic;
+use crate::bounds::{cmp_cut, Cut};
+use crate::range_interval::RangeInterval;
+
+/// The problem of the ranges which do not partition the values between them.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PartitionIssue<T> {
+    /// The range contains no value.
+    Void(RangeInterval<T>),
+    /// The values between two neighbouring ranges are in no range.
+    Gap(RangeInterval<T>),
+    /// The values are in more than one range.
+    Overlap(RangeInterval<T>),
+}
+
+/// The problems of the ranges as a partition, the void ranges first and then the gaps and overlaps from the lowest.
+pub fn partition_issues<T: PartialOrd + Clone>(ranges: &[RangeInterval<T>]) -> Vec<PartitionIssue<T>> {
+    let mut issues: Vec<_> = ranges.iter()
+        .filter(|range| range.is_void())
+        .map(|range| PartitionIssue::Void(range.clone()))
+        .collect();
+    let mut sorted: Vec<_> = ranges.iter().filter(|range| !range.is_void()).collect();
+    sorted.sort_by(|a, b| a.cmp_bounds(b));
+    // the range reaching furthest so far
+    let mut reach: Option<&RangeInterval<T>> = None;
+    for range in sorted {
+        if let Some(last) = reach {
+            if let Some(overlap) = last.intersection(range) {
+                issues.push(PartitionIssue::Overlap(overlap));
+            } else if cmp_cut(&last.end_cut(), &range.start_cut()) == Ordering::Less {
+                let gap = RangeInterval { reverse: false, start: last.end_cut().to_start(), end: range.start_cut().to_end() };
+                issues.push(PartitionIssue::Gap(gap));
+            }
+        }
+        match reach {
+            Some(last) if cmp_cut(&range.end_cut(), &last.end_cut()) != Ordering::Greater => {}
+            _ => reach = Some(range),
+        }
+    }
+    issues
+}
+
+/// Histogram bins, sorted ranges without gaps and overlaps, with the count of the values added to every bin.
+#[derive(Debug, Clone)]
+pub struct Bins<T> {
+    bins: Vec<RangeInterval<T>>,
+    counts: Vec<u64>,
+    outside: u64,
+}
+
+impl<T: PartialOrd + Clone> Bins<T> {
+    /// Create the bins from the ranges in any order, the bins are sorted. All the problems of the partition
+    /// are returned as the error.
+    pub fn new(ranges: impl IntoIterator<Item = RangeInterval<T>>) -> Result<Self, Vec<PartitionIssue<T>>> {
+        let mut bins: Vec<_> = ranges.into_iter().collect();
+        let issues = partition_issues(&bins);
+        if !issues.is_empty() {
+            return Err(issues);
+        }
+        bins.sort_by(|a, b| a.cmp_bounds(b));
+        for bin in &mut bins {
+            bin.reverse = false;
+        }
+        let counts = vec![0; bins.len()];
+        Ok(Self { bins, counts, outside: 0 })
+    }
+
+    /// Create the bins between the increasing edges, with included start and excluded end, except for the last
+    /// bin which includes its end.
+    pub fn from_edges(edges: impl IntoIterator<Item = T>) -> Result<Self, Vec<PartitionIssue<T>>> {
+        let edges: Vec<_> = edges.into_iter().collect();
+        let last = edges.len().saturating_sub(2);
+        Self::new(edges.windows(2).enumerate().map(|(i, pair)| RangeInterval {
+            reverse: false,
+            start: Bound::Included(pair[0].clone()),
+            end: if i == last { Bound::Included(pair[1].clone()) } else { Bound::Excluded(pair[1].clone()) },
+        }))
+    }
+
+    /// The bins, sorted.
+    pub fn bins(&self) -> &[RangeInterval<T>] {
+        &self.bins
+    }
+
+    /// The index of the bin of the value, None if the value is in no bin.
+    pub fn locate(&self, value: &T) -> Option<usize> {
+        let cut = Cut::Below(value);
+        let i = self.bins.partition_point(|bin| cmp_cut(&bin.end_cut(), &cut) != Ordering::Greater);
+        let bin = self.bins.get(i)?;
+        if cmp_cut(&bin.start_cut(), &cut) != Ordering::Greater { Some(i) } else { None }
+    }
+
+    /// Count the value in its bin, or as outside of the bins, and return the index of the bin.
+    pub fn add(&mut self, value: &T) -> Option<usize> {
+        let bin = self.locate(value);
+        match bin {
+            Some(i) => self.counts[i] += 1,
+            None => self.outside += 1,
+        }
+        bin
+    }
+
+    /// The counts of the values in the bins.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// The count of the values in no bin.
+    pub fn outside(&self) -> u64 {
+        self.outside
+    }
+
+    /// Reset all the counts to zero.
+    pub fn clear_counts(&mut self) {
+        self.counts.iter_mut().for_each(|count| *count = 0);
+        self.outside = 0;
+    }
+}
+
+impl<T: Arithmetic + PartialOrd + Clone> Bins<T> {
+    /// Create the count of bins of the width from the start. None if the width is not positive or the edges
+    /// overflow.
+    pub fn equal_width(start: T, width: T, count: usize) -> Option<Self> {
+        if width <= T::zero() {
+            return None;
+        }
+        let mut edges = vec![start];
+        for _ in 0..count {
+            let next = edges[edges.len() - 1].try_add(&width)?;
+            edges.push(next);
+        }
+        Self::from_edges(edges).ok()
+    }
+}
+
+impl Bins<f64> {
+    /// Create the count of bins from the start to the end, with the edges growing by the same ratio. None if
+    /// the start is not positive, or the end is not greater than the start.
+    pub fn log_scale(start: f64, end: f64, count: usize) -> Option<Self> {
+        if !(start > 0.0 && end > start && end.is_finite()) {
+            return None;
+        }
+        let ratio = (end / start).ln() / count as f64;
+        let edges = (0..=count).map(|i| if i == count { end } else { start * (ratio * i as f64).exp() });
+        Self::from_edges(edges).ok()
+    }
+}
+
+impl<T: PartialOrd + Clone> Extend<T> for Bins<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(&value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_issues() {
+        assert_eq!(partition_issues(&[r!(10..20), r!(0..10), r!(20..=30)]), vec![]);
+        assert_eq!(
+            partition_issues(&[r!(0..10), r!(12..20), r!(5..5)]),
+            vec![PartitionIssue::Void(r!(5..5)), PartitionIssue::Gap(r!(10..12))]
+        );
+        assert_eq!(
+            partition_issues(&[r!(0..=10), r!(10..20), r!(!20..30)]),
+            vec![
+                PartitionIssue::Overlap(r!(10..=10)),
+                PartitionIssue::Gap(RangeInterval::new(Bound::Included(20), Bound::Included(20))),
+            ]
+        );
+        assert_eq!(
+            partition_issues(&[r!(0..100), r!(10..20), r!(30..40)]),
+            vec![PartitionIssue::Overlap(r!(10..20)), PartitionIssue::Overlap(r!(30..40))]
+        );
+        assert!(Bins::new(vec![r!(0..10), r!(5..15)]).is_err());
+    }
+
+    #[test]
+    fn test_locate() {
+        let mut bins = Bins::from_edges(vec![0, 10, 20, 30]).unwrap();
+        assert_eq!(bins.bins(), &[r!(0..10), r!(10..20), r!(20..=30)]);
+        assert_eq!(bins.locate(&0), Some(0));
+        assert_eq!(bins.locate(&9), Some(0));
+        assert_eq!(bins.locate(&10), Some(1));
+        assert_eq!(bins.locate(&30), Some(2));
+        assert_eq!(bins.locate(&31), None);
+        assert_eq!(bins.locate(&-1), None);
+        bins.extend(vec![1, 5, 10, 25, 30, 40]);
+        assert_eq!(bins.counts(), &[2, 1, 2]);
+        assert_eq!(bins.outside(), 1);
+        bins.clear_counts();
+        assert_eq!(bins.counts(), &[0, 0, 0]);
+
+        let bins = Bins::new(vec![r!(!0..=5), r!(..=0), r!(!5..)]).unwrap();
+        assert_eq!(bins.locate(&0), Some(0));
+        assert_eq!(bins.locate(&5), Some(1));
+        assert_eq!(bins.locate(&6), Some(2));
+        assert!(Bins::from_edges(vec![0, 10, 5]).is_err());
+    }
+
+    #[test]
+    fn test_constructors() {
+        let bins = Bins::equal_width(0, 5, 3).unwrap();
+        assert_eq!(bins.bins(), &[r!(0..5), r!(5..10), r!(10..=15)]);
+        assert!(Bins::equal_width(0u8, 100, 3).is_none());
+        assert!(Bins::equal_width(0, 0, 3).is_none());
+        let bins = Bins::log_scale(1.0, 1000.0, 3).unwrap();
+        assert_eq!(bins.bins().len(), 3);
+        assert_eq!(bins.locate(&5.0), Some(0));
+        assert_eq!(bins.locate(&50.0), Some(1));
+        assert_eq!(bins.locate(&1000.0), Some(2));
+        assert!(Bins::log_scale(0.0, 10.0, 3).is_none());
+    }
+}
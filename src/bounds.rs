@@ -0,0 +1,178 @@
+//! Ordering of range bounds.
+//!
+//! Every bound of a range is a cut on the number line, either just below a value or just above it.
+//! An included start `<a` and an excluded end `a)` are both cuts below `a`, an excluded start `(a` and an
+//! included end `a>` are cuts above `a`. Unbounded ends are the cuts at the negative and positive infinity.
+//! Comparing the cuts compares the bounds exactly, so `<1..3)` ends where `<3..5>` starts and they do not
+//! overlap, while `<1..3>` and `<3..5>` share the point 3.
+
+use std::cmp::Ordering;
+use std::ops::Bound;
+use crate::range_interval::RangeInterval;
+
+/// A position between values on the number line.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Cut<T> {
+    NegInf,
+    Below(T),
+    Above(T),
+    PosInf,
+}
+
+impl<T> Cut<T> {
+    /// The cut where a range with this start bound begins.
+    pub(crate) fn start(bound: &Bound<T>) -> Cut<&T> {
+        match bound {
+            Bound::Included(v) => Cut::Below(v),
+            Bound::Excluded(v) => Cut::Above(v),
+            Bound::Unbounded => Cut::NegInf,
+        }
+    }
+
+    /// The cut where a range with this end bound ends.
+    pub(crate) fn end(bound: &Bound<T>) -> Cut<&T> {
+        match bound {
+            Bound::Included(v) => Cut::Above(v),
+            Bound::Excluded(v) => Cut::Below(v),
+            Bound::Unbounded => Cut::PosInf,
+        }
+    }
+}
+
+impl<T: Clone> Cut<&T> {
+    /// The start bound of a range beginning at this cut.
+    pub(crate) fn to_start(self) -> Bound<T> {
+        match self {
+            Cut::Below(v) => Bound::Included(v.clone()),
+            Cut::Above(v) => Bound::Excluded(v.clone()),
+            Cut::NegInf | Cut::PosInf => Bound::Unbounded,
+        }
+    }
+
+    /// The end bound of a range ending at this cut.
+    pub(crate) fn to_end(self) -> Bound<T> {
+        match self {
+            Cut::Below(v) => Bound::Excluded(v.clone()),
+            Cut::Above(v) => Bound::Included(v.clone()),
+            Cut::NegInf | Cut::PosInf => Bound::Unbounded,
+        }
+    }
+}
+
+fn rank<T>(cut: &Cut<T>) -> u8 {
+    match cut {
+        Cut::NegInf => 0,
+        Cut::Below(_) => 1,
+        Cut::Above(_) => 2,
+        Cut::PosInf => 3,
+    }
+}
+
+/// Compare two cuts. Values which are not comparable are treated as equal.
+pub(crate) fn cmp_cut<T: PartialOrd>(a: &Cut<&T>, b: &Cut<&T>) -> Ordering {
+    match (a, b) {
+        (Cut::Below(x) | Cut::Above(x), Cut::Below(y) | Cut::Above(y)) => {
+            match x.partial_cmp(y) {
+                Some(Ordering::Equal) | None => rank(a).cmp(&rank(b)),
+                Some(ordering) => ordering,
+            }
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// The later of two cuts.
+pub(crate) fn max_cut<'a, T: PartialOrd>(a: Cut<&'a T>, b: Cut<&'a T>) -> Cut<&'a T> {
+    if cmp_cut(&a, &b) == Ordering::Less { b } else { a }
+}
+
+/// The earlier of two cuts.
+pub(crate) fn min_cut<'a, T: PartialOrd>(a: Cut<&'a T>, b: Cut<&'a T>) -> Cut<&'a T> {
+    if cmp_cut(&a, &b) == Ordering::Greater { b } else { a }
+}
+
+impl<T: PartialOrd> RangeInterval<T> {
+    pub(crate) fn start_cut(&self) -> Cut<&T> {
+        Cut::start(&self.start)
+    }
+
+    pub(crate) fn end_cut(&self) -> Cut<&T> {
+        Cut::end(&self.end)
+    }
+
+    /// Test if there is no value in the range, unlike `is_empty` this also covers start greater than end.
+    pub(crate) fn is_void(&self) -> bool {
+        cmp_cut(&self.start_cut(), &self.end_cut()) != Ordering::Less
+    }
+
+    /// Compare the ranges by their start, and by their end if the starts are the same.
+    pub(crate) fn cmp_bounds(&self, other: &Self) -> Ordering {
+        cmp_cut(&self.start_cut(), &other.start_cut())
+            .then_with(|| cmp_cut(&self.end_cut(), &other.end_cut()))
+    }
+
+    /// Test if there is a value in both ranges.
+    pub(crate) fn overlaps(&self, other: &Self) -> bool {
+        let start = max_cut(self.start_cut(), other.start_cut());
+        let end = min_cut(self.end_cut(), other.end_cut());
+        cmp_cut(&start, &end) == Ordering::Less
+    }
+
+    /// Test if all the values of the other range are in this range.
+    pub(crate) fn covers(&self, other: &Self) -> bool {
+        cmp_cut(&self.start_cut(), &other.start_cut()) != Ordering::Greater
+            && cmp_cut(&other.end_cut(), &self.end_cut()) != Ordering::Greater
+    }
+
+    /// The forward range of the values in both ranges, None if there is no such value.
+    pub(crate) fn intersection(&self, other: &Self) -> Option<Self>
+        where T: Clone
+    {
+        if !self.overlaps(other) {
+            return None;
+        }
+        Some(RangeInterval {
+            reverse: false,
+            start: max_cut(self.start_cut(), other.start_cut()).to_start(),
+            end: min_cut(self.end_cut(), other.end_cut()).to_end(),
+        })
+    }
+
+    /// The forward parts of this range below and above the other range, None for the part without any value.
+    pub(crate) fn difference(&self, other: &Self) -> (Option<Self>, Option<Self>)
+        where T: Clone
+    {
+        let part = |start: Cut<&T>, end: Cut<&T>| match cmp_cut(&start, &end) {
+            Ordering::Less => Some(RangeInterval { reverse: false, start: start.to_start(), end: end.to_end() }),
+            _ => None,
+        };
+        (
+            part(self.start_cut(), min_cut(self.end_cut(), other.start_cut())),
+            part(max_cut(self.start_cut(), other.end_cut()), self.end_cut()),
+        )
+    }
+
+    /// Test if the other range starts before this range ends, or exactly where it ends, so the union of the ranges
+    /// has no gap. The other range must not start before this range.
+    pub(crate) fn connects(&self, other: &Self) -> bool {
+        cmp_cut(&other.start_cut(), &self.end_cut()) != Ordering::Greater
+    }
+}
+
+impl RangeInterval<usize> {
+    /// The offsets of the range as a half-open `start..end`, the unbounded end is the length.
+    /// The range without any value gives an empty `start..start`, the ends past the length are not clamped.
+    pub(crate) fn offsets(&self, len: usize) -> std::ops::Range<usize> {
+        let start = match self.start {
+            Bound::Included(a) => a,
+            Bound::Excluded(a) => a.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match self.end {
+            Bound::Included(b) => b.saturating_add(1),
+            Bound::Excluded(b) => b,
+            Bound::Unbounded => len,
+        };
+        start..end.max(start)
+    }
+}
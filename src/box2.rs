@@ -0,0 +1,198 @@
+//! Two dimensional boxes, the axis aligned rectangles with the ranges on both axes, so every edge of the box
+//! can be open or closed.
+
+
+use std::fmt::{Display, Formatter};
+use std::ops::Bound;
+use crate::arithmetic::Arithmetic;
+use crate::range_interval::{DisplayExt, RangeInterval};
+
+/// Axis aligned rectangle, the product of the ranges on the x and y axes.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Box2<T> {
+    pub x: RangeInterval<T>,
+    pub y: RangeInterval<T>,
+}
+
+/// Subtraction of boxes can yield nothing, or up to four disjoint boxes.
+#[derive(Debug, PartialEq, Clone)]
+pub enum QuadResult<T> {
+    /// The result is an empty set
+    None,
+    /// The result is a single box
+    One(Box2<T>),
+    /// The result is two boxes
+    Two(Box2<T>, Box2<T>),
+    /// The result is three boxes
+    Three(Box2<T>, Box2<T>, Box2<T>),
+    /// The result is four boxes
+    Four(Box2<T>, Box2<T>, Box2<T>, Box2<T>),
+}
+
+impl<T> QuadResult<T> {
+    /// The boxes of the result.
+    pub fn boxes(self) -> Vec<Box2<T>> {
+        match self {
+            QuadResult::None => vec![],
+            QuadResult::One(a) => vec![a],
+            QuadResult::Two(a, b) => vec![a, b],
+            QuadResult::Three(a, b, c) => vec![a, b, c],
+            QuadResult::Four(a, b, c, d) => vec![a, b, c, d],
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> Box2<T> {
+    /// Create the box from the ranges on the x and y axes.
+    pub fn new(x: RangeInterval<T>, y: RangeInterval<T>) -> Self {
+        Self { x, y }
+    }
+
+    /// Test if there is no point in the box.
+    pub fn is_empty(&self) -> bool {
+        self.x.is_void() || self.y.is_void()
+    }
+
+    /// Test if the point is in the box.
+    pub fn contains(&self, x: T, y: T) -> bool {
+        self.x.contains(x) && self.y.contains(y)
+    }
+
+    /// Test if all the points of the other box are in this box.
+    pub fn contains_box(&self, other: &Self) -> bool {
+        other.is_empty() || (self.x.covers(&other.x) && self.y.covers(&other.y))
+    }
+
+    /// Test if there is a point in both boxes.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.x.overlaps(&other.x) && self.y.overlaps(&other.y)
+    }
+
+    /// The box of the points in both boxes, None if there is no such point.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Some(Self { x: self.x.intersection(&other.x)?, y: self.y.intersection(&other.y)? })
+    }
+
+    /// The points of this box which are not in the other box, as disjoint boxes. The parts left and right of
+    /// the other box span the whole height of this box, the parts below and above it are between them.
+    pub fn subtract(self, other: &Self) -> QuadResult<T> {
+        if self.is_empty() {
+            return QuadResult::None;
+        }
+        let middle = match self.intersection(other) {
+            Some(middle) => middle,
+            None => return QuadResult::One(self),
+        };
+        let (left, right) = self.x.difference(&other.x);
+        let (below, above) = self.y.difference(&other.y);
+        let mut boxes = left.map(|x| Box2 { x, y: self.y.clone() }).into_iter()
+            .chain(below.map(|y| Box2 { x: middle.x.clone(), y }))
+            .chain(above.map(|y| Box2 { x: middle.x.clone(), y }))
+            .chain(right.map(|x| Box2 { x, y: self.y.clone() }));
+        match (boxes.next(), boxes.next(), boxes.next(), boxes.next()) {
+            (None, _, _, _) => QuadResult::None,
+            (Some(a), None, _, _) => QuadResult::One(a),
+            (Some(a), Some(b), None, _) => QuadResult::Two(a, b),
+            (Some(a), Some(b), Some(c), None) => QuadResult::Three(a, b, c),
+            (Some(a), Some(b), Some(c), Some(d)) => QuadResult::Four(a, b, c, d),
+        }
+    }
+}
+
+impl<T: Arithmetic + PartialOrd + Clone> Box2<T> {
+    /// The area of the box, the product of the lengths of the ranges. None if the box is unbounded
+    /// or the area overflows.
+    pub fn area(&self) -> Option<T> {
+        if self.is_empty() {
+            return Some(T::zero());
+        }
+        length(&self.x)?.try_mul(&length(&self.y)?)
+    }
+}
+
+/// The length of the non empty range, None if it is unbounded.
+pub(crate) fn length<T: Arithmetic>(range: &RangeInterval<T>) -> Option<T> {
+    match (&range.start, &range.end) {
+        (Bound::Included(a) | Bound::Excluded(a), Bound::Included(b) | Bound::Excluded(b)) => b.try_sub(a),
+        _ => None,
+    }
+}
+
+impl<T: DisplayExt> Display for Box2<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} x {}", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+    use crate::test_util::D;
+
+    fn b(x: RangeInterval<i32>, y: RangeInterval<i32>) -> Box2<i32> {
+        Box2::new(x, y)
+    }
+
+    #[test]
+    fn test_contains() {
+        let a = b(r!(0..10), r!(0..=10));
+        assert!(a.contains(0, 10));
+        assert!(!a.contains(10, 0));
+        assert!(a.contains_box(&b(r!(2..4), r!(!0..=10))));
+        assert!(!a.contains_box(&b(r!(2..=10), r!(0..5))));
+        assert!(a.contains_box(&b(r!(20..30), r!(5..5))));
+        assert!(b(r!(..), r!(..)).contains_box(&a));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = b(r!(0..10), r!(0..=10));
+        assert_eq!(a.intersection(&b(r!(5..15), r!(10..20))), Some(b(r!(5..10), r!(10..=10))));
+        assert_eq!(a.intersection(&b(r!(10..15), r!(0..20))), None);
+        assert_eq!(a.intersection(&b(r!(..), r!(15..5))), Some(b(r!(0..10), r!(!5..=10))));
+        assert!(a.overlaps(&b(r!(9..=9), r!(10..=10))));
+        assert!(!a.overlaps(&b(r!(9..=9), r!(!10..11))));
+    }
+
+    #[test]
+    fn test_subtract() {
+        let a = b(r!(0..10), r!(0..10));
+        assert_eq!(
+            a.clone().subtract(&b(r!(2..4), r!(!5..=6))),
+            QuadResult::Four(
+                b(r!(0..2), r!(0..10)),
+                b(r!(2..4), r!(0..=5)),
+                b(r!(2..4), r!(!6..10)),
+                b(r!(4..10), r!(0..10)),
+            )
+        );
+        assert_eq!(a.clone().subtract(&b(r!(5..), r!(..))), QuadResult::One(b(r!(0..5), r!(0..10))));
+        assert_eq!(
+            a.clone().subtract(&b(r!(..5), r!(5..))),
+            QuadResult::Two(b(r!(0..5), r!(0..5)), b(r!(5..10), r!(0..10)))
+        );
+        assert_eq!(
+            a.clone().subtract(&b(r!(..=5), r!(5..=7))),
+            QuadResult::Three(b(r!(0..=5), r!(0..5)), b(r!(0..=5), r!(!7..10)), b(r!(!5..10), r!(0..10)))
+        );
+        assert_eq!(a.clone().subtract(&b(r!(..), r!(..))), QuadResult::None);
+        assert_eq!(a.clone().subtract(&b(r!(10..20), r!(..))), QuadResult::One(a.clone()));
+        assert_eq!(b(r!(1..1), r!(..)).subtract(&a), QuadResult::None);
+        let parts = a.subtract(&b(r!(3..5), r!(2..8))).boxes();
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts.iter().map(|part| part.area().unwrap()).sum::<i32>(), 100 - 2 * 6);
+    }
+
+    #[test]
+    fn test_area_display() {
+        assert_eq!(b(r!(0..10), r!(5..=7)).area(), Some(20));
+        assert_eq!(b(r!(0..10), r!(5..5)).area(), Some(0));
+        assert_eq!(b(r!(0..10), r!(5..)).area(), None);
+        assert_eq!(Box2::new(r!(0.5..1.0), r!(0.0..=4.0)).area(), Some(2.0));
+
+        let a = Box2 { x: r!(0..10).display::<D>(), y: r!(!5..=7).display::<D>() };
+        assert_eq!(a.to_string(), "<0..10) x (5..7>");
+    }
+}
@@ -0,0 +1,320 @@
+//! N dimensional boxes, the axis aligned hyperrectangles with a range on every axis, and the set of points
+//! kept as a union of disjoint boxes.
+
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use crate::arithmetic::Arithmetic;
+use crate::bounds::cmp_cut;
+use crate::box2::length;
+use crate::range_interval::{DisplayExt, RangeInterval};
+use crate::relation::RangeOrdering;
+
+/// Axis aligned hyperrectangle, the product of the ranges on the axes.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoxN<T, const N: usize> {
+    pub axes: [RangeInterval<T>; N],
+}
+
+impl<T: PartialOrd + Clone, const N: usize> BoxN<T, N> {
+    /// Create the box from the ranges on the axes.
+    pub fn new(axes: [RangeInterval<T>; N]) -> Self {
+        Self { axes }
+    }
+
+    /// Test if there is no point in the box.
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(|axis| axis.is_void())
+    }
+
+    /// Test if the point is in the box.
+    pub fn contains(&self, point: [T; N]) -> bool {
+        self.axes.iter().zip(point).all(|(axis, value)| axis.contains(value))
+    }
+
+    /// Test if all the points of the other box are in this box.
+    pub fn contains_box(&self, other: &Self) -> bool {
+        other.is_empty() || self.axes.iter().zip(&other.axes).all(|(axis, other)| axis.covers(other))
+    }
+
+    /// Test if there is a point in both boxes.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.axes.iter().zip(&other.axes).all(|(axis, other)| axis.overlaps(other))
+    }
+
+    /// The relation of the ranges of this box to the ranges of the other box on every axis.
+    pub fn relation(&self, other: &Self) -> [RangeOrdering; N] {
+        std::array::from_fn(|i| self.axes[i].relation(&other.axes[i]))
+    }
+
+    /// The box of the points in both boxes, None if there is no such point.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if !self.overlaps(other) {
+            return None;
+        }
+        Some(Self { axes: std::array::from_fn(|i| self.axes[i].intersection(&other.axes[i]).expect("axes overlap")) })
+    }
+
+    /// The points of this box which are not in the other box, as at most 2N disjoint boxes. Every axis in turn
+    /// splits off the parts below and above the other box, and the rest continues to the next axis.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        if !self.overlaps(other) {
+            return vec![self.clone()];
+        }
+        let mut boxes = Vec::new();
+        let mut rest = self.clone();
+        for i in 0..N {
+            let (below, above) = rest.axes[i].difference(&other.axes[i]);
+            for part in below.into_iter().chain(above) {
+                let mut split = rest.clone();
+                split.axes[i] = part;
+                boxes.push(split);
+            }
+            rest.axes[i] = rest.axes[i].intersection(&other.axes[i]).expect("axes overlap");
+        }
+        boxes
+    }
+}
+
+impl<T: Arithmetic + PartialOrd + Clone, const N: usize> BoxN<T, N> {
+    /// The volume of the box, the product of the lengths of the ranges. None if the box is unbounded
+    /// or the volume overflows.
+    pub fn volume(&self) -> Option<T> {
+        if self.is_empty() {
+            return Some(T::zero());
+        }
+        let mut axes = self.axes.iter();
+        match axes.next() {
+            Some(first) => axes.try_fold(length(first)?, |volume, axis| volume.try_mul(&length(axis)?)),
+            None => Some(T::zero()),
+        }
+    }
+}
+
+impl<T: DisplayExt, const N: usize> Display for BoxN<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, axis) in self.axes.iter().enumerate() {
+            if i > 0 {
+                write!(f, " x ")?;
+            }
+            write!(f, "{}", axis)?;
+        }
+        Ok(())
+    }
+}
+
+/// Set of points kept as disjoint boxes in a canonical form, so the same points give the same boxes in any order
+/// of the insertions and removals.
+#[derive(Debug, Clone)]
+pub struct BoxSet<T, const N: usize> {
+    boxes: Vec<BoxN<T, N>>,
+}
+
+impl<T, const N: usize> Default for BoxSet<T, N> {
+    fn default() -> Self {
+        Self { boxes: Vec::new() }
+    }
+}
+
+impl<T: PartialOrd + Clone, const N: usize> BoxSet<T, N> {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The disjoint boxes of the set.
+    pub fn boxes(&self) -> &[BoxN<T, N>] {
+        &self.boxes
+    }
+
+    /// Test if there is no point in the set.
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// Test if the point is in the set.
+    pub fn contains(&self, point: [T; N]) -> bool {
+        self.boxes.iter().any(|b| b.contains(point.clone()))
+    }
+
+    /// Add all the points of the box.
+    pub fn insert(&mut self, new: BoxN<T, N>) {
+        let mut parts = vec![new];
+        for b in &self.boxes {
+            parts = parts.iter().flat_map(|part| part.subtract(b)).collect();
+        }
+        self.boxes.extend(parts);
+        self.merge();
+    }
+
+    /// Remove all the points of the box.
+    pub fn remove(&mut self, old: &BoxN<T, N>) {
+        self.boxes = self.boxes.iter().flat_map(|b| b.subtract(old)).collect();
+        self.merge();
+    }
+
+    /// Rebuild the boxes in the canonical form, which depends only on the points of the set.
+    fn merge(&mut self) {
+        let boxes = std::mem::take(&mut self.boxes).into_iter()
+            .filter(|b| !b.is_empty())
+            .map(|mut b| {
+                b.axes.iter_mut().for_each(|axis| axis.reverse = false);
+                b
+            })
+            .collect();
+        self.boxes = canonical(boxes, 0);
+    }
+}
+
+/// The canonical form of the disjoint boxes which have the same ranges on the axes before the axis. The axis is
+/// cut into slabs at all the ends of the boxes, every slab is made canonical on the next axes, and the
+/// neighbouring slabs with the same boxes on the next axes are merged.
+fn canonical<T: PartialOrd + Clone, const N: usize>(boxes: Vec<BoxN<T, N>>, axis: usize) -> Vec<BoxN<T, N>> {
+    if axis == N || boxes.len() <= 1 {
+        return boxes;
+    }
+    let mut cuts: Vec<_> = boxes.iter().map(|b| b.axes[axis].start_cut())
+        .chain(boxes.iter().map(|b| b.axes[axis].end_cut()))
+        .collect();
+    cuts.sort_by(cmp_cut);
+    cuts.dedup_by(|a, b| cmp_cut(a, b) == Ordering::Equal);
+    let same = |a: &[BoxN<T, N>], b: &[BoxN<T, N>]| {
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.axes[axis + 1..] == y.axes[axis + 1..])
+    };
+
+    let mut result = Vec::new();
+    // the current merged slab, with the index of its last slab and its boxes on the next axes
+    let mut current: Option<(RangeInterval<T>, usize, Vec<BoxN<T, N>>)> = None;
+    for (i, pair) in cuts.windows(2).enumerate() {
+        let slab = RangeInterval { reverse: false, start: pair[0].to_start(), end: pair[1].to_end() };
+        let section: Vec<_> = boxes.iter()
+            .filter(|b| b.axes[axis].covers(&slab))
+            .map(|b| {
+                let mut b = b.clone();
+                b.axes[axis] = slab.clone();
+                b
+            })
+            .collect();
+        if section.is_empty() {
+            continue;
+        }
+        let section = canonical(section, axis + 1);
+        current = match current.take() {
+            Some((range, last, boxes)) if last + 1 == i && same(&boxes, &section) => {
+                Some((RangeInterval { end: slab.end, ..range }, i, boxes))
+            }
+            previous => {
+                result.extend(previous.into_iter().flat_map(|(range, _, boxes)| with_axis(boxes, axis, range)));
+                Some((slab, i, section))
+            }
+        };
+    }
+    result.extend(current.into_iter().flat_map(|(range, _, boxes)| with_axis(boxes, axis, range)));
+    result
+}
+
+/// The boxes with the range on the axis.
+fn with_axis<T: Clone, const N: usize>(boxes: Vec<BoxN<T, N>>, axis: usize, range: RangeInterval<T>) -> Vec<BoxN<T, N>> {
+    boxes.into_iter()
+        .map(|mut b| {
+            b.axes[axis] = range.clone();
+            b
+        })
+        .collect()
+}
+
+impl<T: Arithmetic + PartialOrd + Clone, const N: usize> BoxSet<T, N> {
+    /// The volume of the set, the sum of the volumes of the boxes. None if the set is unbounded
+    /// or the volume overflows.
+    pub fn volume(&self) -> Option<T> {
+        self.boxes.iter().try_fold(T::zero(), |volume, b| volume.try_add(&b.volume()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_box() {
+        let a = BoxN::new([r!(0..10), r!(0..10), r!(0..=10)]);
+        assert!(a.contains([0, 9, 10]));
+        assert!(!a.contains([0, 10, 10]));
+        assert_eq!(a.volume(), Some(1000));
+        assert!(a.contains_box(&BoxN::new([r!(2..4), r!(2..4), r!(10..=10)])));
+        assert!(!a.contains_box(&BoxN::new([r!(2..4), r!(2..=10), r!(10..=10)])));
+        let b = BoxN::new([r!(5..), r!(..), r!(10..=20)]);
+        assert_eq!(
+            a.relation(&b),
+            [RangeOrdering::LessOverlap, RangeOrdering::Within, RangeOrdering::LessOverlap]
+        );
+        assert_eq!(a.intersection(&b), Some(BoxN::new([r!(5..10), r!(0..10), r!(10..=10)])));
+        assert_eq!(a.intersection(&BoxN::new([r!(5..), r!(..), r!(!10..=20)])), None);
+        assert_eq!(BoxN::new([r!(0..10), r!(5..)]).volume(), None);
+        assert_eq!(BoxN::<i32, 0>::new([]).volume(), Some(0));
+    }
+
+    #[test]
+    fn test_subtract() {
+        let a = BoxN::new([r!(0..10), r!(0..10), r!(0..10)]);
+        let parts = a.subtract(&BoxN::new([r!(2..4), r!(2..4), r!(2..4)]));
+        assert_eq!(parts.len(), 6);
+        assert_eq!(parts[0], BoxN::new([r!(0..2), r!(0..10), r!(0..10)]));
+        assert_eq!(parts[2], BoxN::new([r!(2..4), r!(0..2), r!(0..10)]));
+        assert_eq!(parts[5], BoxN::new([r!(2..4), r!(2..4), r!(4..10)]));
+        assert_eq!(parts.iter().map(|part| part.volume().unwrap()).sum::<i32>(), 1000 - 8);
+        for (i, part) in parts.iter().enumerate() {
+            assert!(parts[i + 1..].iter().all(|other| !part.overlaps(other)));
+        }
+        assert_eq!(a.subtract(&BoxN::new([r!(..), r!(5..), r!(..)])), vec![BoxN::new([r!(0..10), r!(0..5), r!(0..10)])]);
+        assert_eq!(a.subtract(&BoxN::new([r!(10..), r!(..), r!(..)])), vec![a.clone()]);
+        assert_eq!(a.subtract(&BoxN::new([r!(..), r!(..), r!(..)])), vec![]);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut set = BoxSet::new();
+        set.insert(BoxN::new([r!(0..10), r!(0..10)]));
+        set.insert(BoxN::new([r!(5..15), r!(0..10)]));
+        assert_eq!(set.boxes(), &[BoxN::new([r!(0..15), r!(0..10)])]);
+        set.insert(BoxN::new([r!(5..15), r!(5..20)]));
+        assert_eq!(set.volume(), Some(150 + 100));
+        assert!(set.contains([14, 19]));
+        assert!(!set.contains([4, 19]));
+        set.remove(&BoxN::new([r!(5..15), r!(5..20)]));
+        assert_eq!(set.volume(), Some(100));
+        set.remove(&BoxN::new([r!(0..5), r!(..)]));
+        assert_eq!(set.boxes(), &[BoxN::new([r!(5..15), r!(0..5)])]);
+        set.remove(&BoxN::new([r!(..), r!(..)]));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_set_canonical() {
+        let boxes = [
+            BoxN::new([r!(0..10), r!(0..5)]),
+            BoxN::new([r!(0..5), r!(0..10)]),
+            BoxN::new([r!(20..8), r!(3..4)]),
+            BoxN::new([r!(5..10), r!(8..12)]),
+        ];
+        let mut forward = BoxSet::new();
+        boxes.iter().cloned().for_each(|b| forward.insert(b));
+        let mut backward = BoxSet::new();
+        boxes.iter().rev().cloned().for_each(|b| backward.insert(b));
+        assert_eq!(forward.boxes(), backward.boxes());
+        assert_eq!(forward.volume(), Some(50 + 25 + 10 + 20));
+        assert_eq!(forward.boxes()[0], BoxN::new([r!(0..5), r!(0..10)]));
+        assert!(forward.boxes().iter().all(|b| b.axes.iter().all(|axis| !axis.reverse)));
+
+        let mut set = BoxSet::new();
+        set.insert(BoxN::new([r!(0..2), r!(0..2)]));
+        set.insert(BoxN::new([r!(0..2), r!(2..4)]));
+        set.insert(BoxN::new([r!(2..4), r!(0..4)]));
+        assert_eq!(set.boxes(), &[BoxN::new([r!(0..4), r!(0..4)])]);
+    }
+}
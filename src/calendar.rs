@@ -0,0 +1,188 @@
+//! Availability calendar, the occurrences of recurring rules like the working hours, minus the busy ranges like
+//! bookings and holidays. The time values can be any integer type, for example `u64` seconds since the epoch.
+
+
+use std::ops::Bound;
+use crate::arithmetic::Arithmetic;
+use crate::bounds::Cut;
+use crate::normalize::normalize;
+use crate::range_interval::RangeInterval;
+use crate::successor::Successor;
+
+/// Rule repeating the range `<offset + k * period..offset + k * period + length)` for every k from 0.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Recurrence<T> {
+    pub period: T,
+    pub offset: T,
+    pub length: T,
+}
+
+impl<T: Arithmetic + PartialOrd + Clone> Recurrence<T> {
+    /// Create the rule, for example every day starting at 9h for 8h is `Recurrence::new(86400, 9 * 3600, 8 * 3600)`.
+    pub fn new(period: T, offset: T, length: T) -> Self {
+        Self { period, offset, length }
+    }
+
+    /// The occurrences of the rule overlapping the window, cut to the window. Returns None if the window has
+    /// no end, or the values overflow.
+    pub fn occurrences(&self, window: &RangeInterval<T>) -> Option<Vec<RangeInterval<T>>> {
+        let mut result = Vec::new();
+        let end = match &window.end {
+            Bound::Included(end) | Bound::Excluded(end) => end,
+            Bound::Unbounded => return None,
+        };
+        if self.period <= T::zero() || self.length <= T::zero() {
+            return Some(result);
+        }
+        // the first occurrence which can end in the window
+        let before = match &window.start {
+            Bound::Included(start) | Bound::Excluded(start) => start.try_sub(&self.offset)
+                .and_then(|after| after.try_sub(&self.length)),
+            Bound::Unbounded => None,
+        };
+        let mut start = match before {
+            Some(before) if before > T::zero() => {
+                let count = before.try_div(&self.period)?;
+                self.offset.try_add(&count.try_mul(&self.period)?)?
+            }
+            _ => self.offset.clone(),
+        };
+        while start <= *end {
+            let occurrence = RangeInterval {
+                reverse: false,
+                start: Bound::Included(start.clone()),
+                end: Bound::Excluded(start.try_add(&self.length)?),
+            };
+            result.extend(occurrence.intersection(window));
+            start = match start.try_add(&self.period) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        Some(result)
+    }
+}
+
+/// Calendar of the availability, the occurrences of the rules minus the busy ranges.
+#[derive(Debug, Clone)]
+pub struct Calendar<T> {
+    rules: Vec<Recurrence<T>>,
+    busy: Vec<RangeInterval<T>>,
+}
+
+impl<T> Default for Calendar<T> {
+    fn default() -> Self {
+        Self { rules: Vec::new(), busy: Vec::new() }
+    }
+}
+
+impl<T: Arithmetic + PartialOrd + Clone> Calendar<T> {
+    /// Create the calendar without any availability.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the rule of the available time.
+    pub fn add_rule(&mut self, rule: Recurrence<T>) {
+        self.rules.push(rule);
+    }
+
+    /// Add the busy range, like a booking or a holiday.
+    pub fn add_busy(&mut self, range: RangeInterval<T>) {
+        self.busy.push(range);
+    }
+
+    /// The sorted disjoint available ranges within the window. Returns None if the window has no end,
+    /// or the values overflow.
+    pub fn available(&self, window: &RangeInterval<T>) -> Option<Vec<RangeInterval<T>>> {
+        let mut available = Vec::new();
+        for rule in &self.rules {
+            available.extend(rule.occurrences(window)?);
+        }
+        let mut available = normalize(available);
+        for busy in &self.busy {
+            available = available.into_iter()
+                .flat_map(|part| part.subtract(busy.clone()).into_vec())
+                .collect();
+        }
+        Some(normalize(available))
+    }
+
+    /// The first available range of the length within the window, starting at the first value of an available
+    /// range, so the slot after the busy range `<0..=3>` starts at 4. Returns None if there is no such range.
+    pub fn first_free(&self, window: &RangeInterval<T>, length: T) -> Option<RangeInterval<T>>
+        where T: Successor
+    {
+        self.available(window)?.into_iter().filter_map(|free| free.half_open()).find_map(|free| {
+            let start = match &free.start {
+                Bound::Included(start) | Bound::Excluded(start) => start,
+                Bound::Unbounded => return None,
+            };
+            let end = start.try_add(&length)?;
+            let slot = RangeInterval { reverse: false, start: free.start.clone(), end: Bound::Excluded(end) };
+            if free.covers(&slot) && Cut::start(&slot.start) != Cut::end(&slot.end) {
+                Some(slot)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    const DAY: u64 = 86400;
+    const HOUR: u64 = 3600;
+
+    #[test]
+    fn test_occurrences() {
+        let rule = Recurrence::new(DAY, 9 * HOUR, 8 * HOUR);
+        assert_eq!(rule.occurrences(&r!(0..DAY)), Some(vec![r!(9 * HOUR..17 * HOUR)]));
+        assert_eq!(rule.occurrences(&r!(0..)), None);
+        assert_eq!(
+            rule.occurrences(&r!(10 * DAY + 10 * HOUR..=12 * DAY + 9 * HOUR)),
+            Some(vec![
+                r!(10 * DAY + 10 * HOUR..10 * DAY + 17 * HOUR),
+                r!(11 * DAY + 9 * HOUR..11 * DAY + 17 * HOUR),
+                r!(12 * DAY + 9 * HOUR..=12 * DAY + 9 * HOUR),
+            ])
+        );
+        assert_eq!(rule.occurrences(&r!(17 * HOUR..DAY + 9 * HOUR)), Some(vec![]));
+
+        let rule = Recurrence::new(10i32, 5, 15);
+        assert_eq!(rule.occurrences(&r!(22..=40)), Some(vec![r!(22..30), r!(25..40), r!(35..=40)]));
+        assert_eq!(rule.occurrences(&r!(..12)), Some(vec![r!(5..12)]));
+    }
+
+    #[test]
+    fn test_available() {
+        let mut calendar = Calendar::new();
+        calendar.add_rule(Recurrence::new(DAY, 9 * HOUR, 8 * HOUR));
+        calendar.add_rule(Recurrence::new(DAY, 16 * HOUR, 2 * HOUR));
+        calendar.add_busy(r!(DAY..2 * DAY));
+        calendar.add_busy(r!(10 * HOUR..=11 * HOUR));
+        assert_eq!(
+            calendar.available(&r!(0..3 * DAY)),
+            Some(vec![
+                r!(9 * HOUR..10 * HOUR),
+                r!(!11 * HOUR..18 * HOUR),
+                r!(2 * DAY + 9 * HOUR..2 * DAY + 18 * HOUR),
+            ])
+        );
+        assert_eq!(calendar.first_free(&r!(0..3 * DAY), HOUR), Some(r!(9 * HOUR..10 * HOUR)));
+        assert_eq!(calendar.first_free(&r!(0..3 * DAY), 2 * HOUR), Some(r!(11 * HOUR + 1..13 * HOUR + 1)));
+        assert_eq!(calendar.first_free(&r!(0..3 * DAY), 8 * HOUR), Some(r!(2 * DAY + 9 * HOUR..2 * DAY + 17 * HOUR)));
+        assert_eq!(calendar.first_free(&r!(0..3 * DAY), 10 * HOUR), None);
+        assert_eq!(calendar.first_free(&r!(0..), HOUR), None);
+
+        let mut calendar = Calendar::new();
+        calendar.add_rule(Recurrence::new(100u64, 0, 10));
+        calendar.add_busy(r!(0..=3));
+        assert_eq!(calendar.first_free(&r!(0..100), 6), Some(r!(4..10)));
+        assert_eq!(calendar.first_free(&r!(0..100), 7), None);
+    }
+}
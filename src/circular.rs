@@ -0,0 +1,221 @@
+//! Circular intervals on the values `0..modulus` which wrap around, like angles, time of the day or ring buffer
+//! indices. The interval `<22..2)` of the hours is from 22 through the midnight to 2, not a reversed range.
+//! The interval starting after it ends wraps around, the one starting where it ends is the whole circle.
+
+
+use std::cmp::Ordering;
+use std::ops::Bound;
+use crate::arithmetic::Arithmetic;
+use crate::binary_result::BinaryResult;
+use crate::bounds::{cmp_cut, Cut};
+use crate::normalize::normalize;
+use crate::range_interval::RangeInterval;
+
+/// Interval on the circle of the values `0..modulus`, from the start bound in the increasing direction
+/// to the end bound, wrapping around after the modulus.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CircularInterval<T> {
+    pub start: Bound<T>,
+    pub end: Bound<T>,
+    pub modulus: T,
+}
+
+/// Operations on circular intervals can yield nothing, one or two intervals.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CircularResult<T> {
+    /// The result is an empty set
+    None,
+    /// The result is a single interval
+    One(CircularInterval<T>),
+    /// The result is two intervals
+    Two(CircularInterval<T>, CircularInterval<T>),
+}
+
+impl<T: Arithmetic + PartialOrd + Clone> CircularInterval<T> {
+    /// Create the interval, the unbounded start is 0 and the unbounded end is the modulus.
+    pub fn new(start: Bound<T>, end: Bound<T>, modulus: T) -> Self {
+        let start = match start {
+            Bound::Unbounded => Bound::Included(T::zero()),
+            start => start,
+        };
+        let end = match end {
+            Bound::Unbounded => Bound::Excluded(modulus.clone()),
+            end => end,
+        };
+        Self { start, end, modulus }
+    }
+
+    /// Create the interval from the range, the reversed range like `r!(22..2)` wraps around.
+    pub fn from_range(range: RangeInterval<T>, modulus: T) -> Self {
+        if range.reverse {
+            Self::new(range.end, range.start, modulus)
+        } else {
+            Self::new(range.start, range.end, modulus)
+        }
+    }
+
+    /// The whole circle.
+    pub fn full(modulus: T) -> Self {
+        Self::new(Bound::Unbounded, Bound::Unbounded, modulus)
+    }
+
+    /// Test if the interval goes through the modulus back to 0.
+    pub fn is_wrapping(&self) -> bool {
+        cmp_cut(&Cut::start(&self.start), &Cut::end(&self.end)) != Ordering::Less
+    }
+
+    /// The non empty forward pieces of the interval within `0..modulus`.
+    fn pieces(&self) -> Vec<RangeInterval<T>> {
+        let circle = RangeInterval {
+            reverse: false,
+            start: Bound::Included(T::zero()),
+            end: Bound::Excluded(self.modulus.clone()),
+        };
+        let pieces = if self.is_wrapping() {
+            vec![
+                RangeInterval { reverse: false, start: circle.start.clone(), end: self.end.clone() },
+                RangeInterval { reverse: false, start: self.start.clone(), end: circle.end.clone() },
+            ]
+        } else {
+            vec![RangeInterval { reverse: false, start: self.start.clone(), end: self.end.clone() }]
+        };
+        pieces.iter().filter_map(|piece| piece.intersection(&circle)).collect()
+    }
+
+    /// The intervals made of the pieces, the pieces at 0 and at the modulus are joined to a wrapping interval.
+    fn from_pieces(pieces: Vec<RangeInterval<T>>, modulus: &T) -> CircularResult<T> {
+        let mut pieces = normalize(pieces);
+        let zero = T::zero();
+        let joined = if pieces.len() > 1
+            && cmp_cut(&pieces[0].start_cut(), &Cut::Below(&zero)) != Ordering::Greater
+            && cmp_cut(&pieces[pieces.len() - 1].end_cut(), &Cut::Below(modulus)) != Ordering::Less
+        {
+            let first = pieces.remove(0);
+            let last = pieces.pop().expect("more pieces");
+            Some(CircularInterval { start: last.start, end: first.end, modulus: modulus.clone() })
+        } else {
+            None
+        };
+        let mut intervals = pieces.into_iter()
+            .map(|piece| CircularInterval { start: piece.start, end: piece.end, modulus: modulus.clone() })
+            .chain(joined);
+        match (intervals.next(), intervals.next(), intervals.next()) {
+            (None, _, _) => CircularResult::None,
+            (Some(a), None, _) => CircularResult::One(a),
+            (Some(a), Some(b), None) => CircularResult::Two(a, b),
+            _ => unreachable!("the intersection of two circular intervals has at most two intervals"),
+        }
+    }
+
+    /// Test if the value in `0..modulus` is in the interval.
+    pub fn contains(&self, value: T) -> bool {
+        self.pieces().iter().any(|piece| piece.contains(value.clone()))
+    }
+
+    /// The values in both intervals, which can be two intervals when both wrap around.
+    pub fn intersection(&self, other: &Self) -> CircularResult<T> {
+        let others = other.pieces();
+        let pieces = self.pieces().iter()
+            .flat_map(|piece| others.iter().filter_map(move |other| piece.intersection(other)))
+            .collect();
+        Self::from_pieces(pieces, &self.modulus)
+    }
+
+    /// The values of this interval which are not in the other interval.
+    pub fn subtract(&self, other: &Self) -> CircularResult<T> {
+        let mut pieces = self.pieces();
+        for other in other.pieces() {
+            pieces = pieces.iter()
+                .flat_map(|piece| {
+                    if piece.overlaps(&other) {
+                        let (below, above) = piece.difference(&other);
+                        below.into_iter().chain(above).collect()
+                    } else {
+                        vec![piece.clone()]
+                    }
+                })
+                .collect();
+        }
+        Self::from_pieces(pieces, &self.modulus)
+    }
+
+    /// The non wrapping ranges of the interval, split at the modulus, sorted from 0.
+    pub fn to_ranges(&self) -> BinaryResult<T> {
+        let mut pieces = self.pieces().into_iter();
+        match (pieces.next(), pieces.next()) {
+            (None, _) => BinaryResult::None,
+            (Some(a), None) => BinaryResult::One(a),
+            (Some(a), Some(b)) => BinaryResult::Two(a, b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    fn c(range: RangeInterval<u32>) -> CircularInterval<u32> {
+        CircularInterval::from_range(range, 24)
+    }
+
+    #[test]
+    fn test_contains() {
+        let night = c(r!(22..2));
+        assert!(night.is_wrapping());
+        assert!(night.contains(23));
+        assert!(night.contains(0));
+        assert!(night.contains(22));
+        assert!(!night.contains(2));
+        assert!(!night.contains(12));
+        let day = c(r!(8..=17));
+        assert!(!day.is_wrapping());
+        assert!(day.contains(17));
+        assert!(!day.contains(23));
+        let almost = CircularInterval::new(Bound::Excluded(5), Bound::Excluded(5), 24);
+        assert!(almost.contains(4));
+        assert!(almost.contains(6));
+        assert!(!almost.contains(5));
+        assert!(CircularInterval::full(24).contains(0));
+    }
+
+    #[test]
+    fn test_to_ranges() {
+        assert_eq!(c(r!(22..2)).to_ranges(), BinaryResult::Two(r!(0..2), r!(22..24)));
+        assert_eq!(c(r!(22..0)).to_ranges(), BinaryResult::One(r!(22..24)));
+        assert_eq!(c(r!(8..=17)).to_ranges(), BinaryResult::One(r!(8..=17)));
+        assert_eq!(CircularInterval::full(24).to_ranges(), BinaryResult::One(r!(0..24)));
+        let whole = CircularInterval::new(Bound::Included(5), Bound::Excluded(5), 24);
+        assert_eq!(whole.to_ranges(), BinaryResult::Two(r!(0..5), r!(5..24)));
+    }
+
+    #[test]
+    fn test_intersection() {
+        assert_eq!(c(r!(22..2)).intersection(&c(r!(8..=17))), CircularResult::None);
+        assert_eq!(c(r!(22..2)).intersection(&c(r!(1..=23))), CircularResult::Two(c(r!(1..2)), c(r!(22..=23))));
+        assert_eq!(c(r!(22..4)).intersection(&c(r!(20..3))), CircularResult::One(c(r!(22..3))));
+        assert_eq!(c(r!(22..4)).intersection(&c(r!(0..3))), CircularResult::One(c(r!(0..3))));
+        assert_eq!(
+            c(r!(20..18)).intersection(&c(r!(16..14))),
+            CircularResult::Two(c(r!(16..18)), c(r!(20..14)))
+        );
+        assert_eq!(c(r!(22..2)).intersection(&CircularInterval::full(24)), CircularResult::One(c(r!(22..2))));
+    }
+
+    #[test]
+    fn test_subtract() {
+        assert_eq!(c(r!(22..2)).subtract(&c(r!(8..=17))), CircularResult::One(c(r!(22..2))));
+        assert_eq!(
+            c(r!(22..2)).subtract(&c(r!(23..=23))),
+            CircularResult::Two(c(r!(22..23)), CircularInterval::new(Bound::Excluded(23), Bound::Excluded(2), 24))
+        );
+        assert_eq!(c(r!(22..2)).subtract(&c(r!(1..23))), CircularResult::One(c(r!(23..1))));
+        assert_eq!(
+            CircularInterval::full(24).subtract(&c(r!(8..=17))),
+            CircularResult::One(CircularInterval::new(Bound::Excluded(17), Bound::Excluded(8), 24))
+        );
+        assert_eq!(c(r!(8..=17)).subtract(&c(r!(10..12))), CircularResult::Two(c(r!(8..10)), c(r!(12..=17))));
+        assert_eq!(c(r!(8..=17)).subtract(&c(r!(2..20))), CircularResult::None);
+    }
+}
@@ -0,0 +1,174 @@
+//! Tracking which parts of a target range have been received, for example the byte ranges of a download.
+//! The chunks can arrive in any order, overlapping or duplicated, the tracker merges them and reports what is
+//! still missing.
+
+
+use std::cmp::Ordering;
+use crate::bounds::{cmp_cut, max_cut, min_cut};
+use crate::range_interval::RangeInterval;
+use crate::successor::Successor;
+
+/// A change of the coverage caused by a received chunk.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CoverageEvent<T> {
+    /// The contiguous part received from the start of the target has grown, this is the whole received prefix.
+    PrefixGrown(RangeInterval<T>),
+    /// The whole target has been received.
+    Complete,
+}
+
+/// Coverage of the target range by the received chunks. The values are discrete, so the chunks `<0..=9>`
+/// and `<10..=19>` are contiguous. All the reported ranges have included start and excluded end, unless the
+/// end is the maximal value.
+#[derive(Debug, Clone)]
+pub struct Coverage<T> {
+    target: Option<RangeInterval<T>>,
+    received: Vec<RangeInterval<T>>,
+    /// The sorted parts of the target not received yet, updated by every chunk.
+    missing: Vec<RangeInterval<T>>,
+}
+
+impl<T: PartialOrd + Successor + Clone> Coverage<T> {
+    /// Create the coverage of the target with nothing received. The reversed target is tracked forward.
+    pub fn new(target: RangeInterval<T>) -> Self {
+        let target = RangeInterval { reverse: false, ..target }.half_open();
+        Self { missing: target.iter().cloned().collect(), target, received: Vec::new() }
+    }
+
+    /// The target range, None if there is no value in the target.
+    pub fn target(&self) -> Option<&RangeInterval<T>> {
+        self.target.as_ref()
+    }
+
+    /// The received ranges, merged and sorted, including the parts outside of the target.
+    pub fn received(&self) -> &[RangeInterval<T>] {
+        &self.received
+    }
+
+    /// Add a received chunk, and return the events it caused. Only the received and missing ranges touching
+    /// the chunk are updated.
+    pub fn insert(&mut self, chunk: RangeInterval<T>) -> Vec<CoverageEvent<T>> {
+        let chunk = match (RangeInterval { reverse: false, ..chunk }).half_open() {
+            Some(chunk) => chunk,
+            None => return Vec::new(),
+        };
+        let prefix = self.prefix();
+        let complete = self.is_complete();
+
+        // the received ranges overlapping or touching the chunk are merged with it
+        let lo = self.received.partition_point(|r| cmp_cut(&r.end_cut(), &chunk.start_cut()) == Ordering::Less);
+        let hi = self.received.partition_point(|r| cmp_cut(&r.start_cut(), &chunk.end_cut()) != Ordering::Greater);
+        let merged = if lo < hi {
+            RangeInterval {
+                reverse: false,
+                start: min_cut(self.received[lo].start_cut(), chunk.start_cut()).to_start(),
+                end: max_cut(self.received[hi - 1].end_cut(), chunk.end_cut()).to_end(),
+            }
+        } else {
+            chunk.clone()
+        };
+        self.received.splice(lo..hi, Some(merged));
+
+        // the chunk is subtracted from the missing parts it overlaps
+        let lo = self.missing.partition_point(|m| cmp_cut(&m.end_cut(), &chunk.start_cut()) != Ordering::Greater);
+        let hi = self.missing.partition_point(|m| cmp_cut(&m.start_cut(), &chunk.end_cut()) == Ordering::Less);
+        let parts: Vec<_> = self.missing[lo..hi].iter()
+            .flat_map(|part| part.clone().subtract(chunk.clone()).into_vec())
+            .filter_map(|part| part.half_open())
+            .collect();
+        self.missing.splice(lo..hi, parts);
+
+        let mut events = Vec::new();
+        match self.prefix() {
+            Some(grown) if prefix.as_ref() != Some(&grown) => events.push(CoverageEvent::PrefixGrown(grown)),
+            _ => {}
+        }
+        if !complete && self.is_complete() {
+            events.push(CoverageEvent::Complete);
+        }
+        events
+    }
+
+    /// The sorted parts of the target which have not been received yet.
+    pub fn missing(&self) -> &[RangeInterval<T>] {
+        &self.missing
+    }
+
+    /// Test if the whole target has been received.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// The contiguous part received from the start of the target, None if the start has not been received.
+    pub fn prefix(&self) -> Option<RangeInterval<T>> {
+        let target = self.target.as_ref()?;
+        match self.missing.first() {
+            None => Some(target.clone()),
+            Some(missing) if missing.start == target.start => None,
+            Some(missing) => RangeInterval {
+                reverse: false,
+                start: target.start.clone(),
+                end: missing.start_cut().to_end(),
+            }.half_open(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_coverage() {
+        let mut coverage = Coverage::new(r!(0u64..100));
+        assert_eq!(coverage.missing(), vec![r!(0..100)]);
+        assert_eq!(coverage.prefix(), None);
+        assert!(!coverage.is_complete());
+
+        assert_eq!(coverage.insert(r!(10..=19)), vec![]);
+        assert_eq!(coverage.missing(), vec![r!(0..10), r!(20..100)]);
+
+        assert_eq!(coverage.insert(r!(30..40)), vec![]);
+        assert_eq!(coverage.missing(), vec![r!(0..10), r!(20..30), r!(40..100)]);
+
+        assert_eq!(coverage.insert(r!(0..=9)), vec![CoverageEvent::PrefixGrown(r!(0..20))]);
+        assert_eq!(coverage.missing(), vec![r!(20..30), r!(40..100)]);
+        assert_eq!(coverage.prefix(), Some(r!(0..20)));
+
+        assert_eq!(coverage.insert(r!(5..15)), vec![]);
+        assert_eq!(coverage.insert(r!(!80..=200)), vec![]);
+        assert_eq!(coverage.missing(), vec![r!(20..30), r!(40..81)]);
+        assert_eq!(coverage.received(), &[r!(0..20), r!(30..40), r!(81..201)]);
+
+        assert_eq!(coverage.insert(r!(!19..40)), vec![CoverageEvent::PrefixGrown(r!(0..40))]);
+        assert_eq!(
+            coverage.insert(r!(40..=80)),
+            vec![CoverageEvent::PrefixGrown(r!(0..100)), CoverageEvent::Complete]
+        );
+        assert!(coverage.is_complete());
+        assert_eq!(coverage.missing(), vec![]);
+        assert_eq!(coverage.insert(r!(0..10)), vec![]);
+    }
+
+    #[test]
+    fn test_coverage_bounds() {
+        let mut coverage = Coverage::new(r!(!0u8..=255));
+        assert_eq!(coverage.target(), Some(&r!(1..=255)));
+        assert_eq!(coverage.insert(r!(200..)), vec![]);
+        assert_eq!(coverage.missing(), vec![r!(1..200)]);
+        assert_eq!(coverage.insert(r!(..200)), vec![CoverageEvent::PrefixGrown(r!(1..=255)), CoverageEvent::Complete]);
+
+        let mut coverage = Coverage::new(r!(!0u8..1));
+        assert_eq!(coverage.target(), None);
+        assert!(coverage.is_complete());
+        assert_eq!(coverage.insert(r!(0..1)), vec![]);
+
+        let mut coverage = Coverage::new(r!(100u32..0));
+        assert_eq!(coverage.missing(), &[r!(1..101)]);
+        assert_eq!(coverage.insert(r!(50..0)), vec![CoverageEvent::PrefixGrown(r!(1..51))]);
+        assert_eq!(coverage.missing(), &[r!(51..101)]);
+        assert!(coverage.missing().iter().all(|part| !part.reverse));
+    }
+}
@@ -0,0 +1,307 @@
+//! Set of values of a small integer domain, like ports or byte classes, backed by a fixed bit array.
+//! The ranges are inserted and removed by whole words, and the set can be exported back to the minimal list of ranges.
+
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::Bound;
+use crate::range_interval::RangeInterval;
+use crate::successor::Successor;
+
+/// The number of bits in a word of the set.
+pub const WORD_BITS: usize = 64;
+
+/// Integer domain small enough to be stored as a bit array, every value has its own bit.
+pub trait DenseIndex: Copy + PartialOrd + Successor {
+    /// The number of values in the domain.
+    const SIZE: usize;
+
+    /// The bit of the value.
+    fn to_index(self) -> usize;
+
+    /// The value of the bit.
+    fn from_index(index: usize) -> Self;
+}
+
+macro_rules! impl_dense {
+    ($t: ty) => {
+        impl DenseIndex for $t {
+            const SIZE: usize = 1 << <$t>::BITS;
+
+            fn to_index(self) -> usize {
+                self as usize
+            }
+
+            fn from_index(index: usize) -> Self {
+                index as $t
+            }
+        }
+    }
+}
+
+impl_dense!(u8);
+impl_dense!(u16);
+
+/// Set of values stored as a bit array, with one bit for each value of the domain.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DenseRangeSet<T> {
+    words: Vec<u64>,
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<T: DenseIndex> Default for DenseRangeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: DenseIndex> DenseRangeSet<T> {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self { words: vec![0; T::SIZE.div_ceil(WORD_BITS)], marker: std::marker::PhantomData }
+    }
+
+    /// The bits of the range, inclusive from both sides, None if there is no value in the range.
+    fn bits(range: RangeInterval<T>) -> Option<(usize, usize)> {
+        let closed = range.closed()?;
+        let first = match closed.start {
+            Bound::Included(a) => a.to_index(),
+            _ => 0,
+        };
+        let last = match closed.end {
+            Bound::Included(b) => b.to_index(),
+            _ => T::SIZE - 1,
+        };
+        if first > last {
+            None
+        } else {
+            Some((first, last))
+        }
+    }
+
+    /// The words with the masks of the bits from the first to the last, the words between the first and the last
+    /// word have all the bits set.
+    fn masks(first: usize, last: usize) -> impl Iterator<Item = (usize, u64)> {
+        let (first_word, last_word) = (first / WORD_BITS, last / WORD_BITS);
+        (first_word..=last_word).map(move |word| {
+            let low = if word == first_word { first % WORD_BITS } else { 0 };
+            let high = if word == last_word { last % WORD_BITS } else { WORD_BITS - 1 };
+            (word, (!0u64 >> (WORD_BITS - 1 - high)) & (!0u64 << low))
+        })
+    }
+
+    /// Set or clear the bits from the first to the last, by whole words.
+    fn fill(&mut self, first: usize, last: usize, value: bool) {
+        for (word, mask) in Self::masks(first, last) {
+            if value {
+                self.set_word(word, mask);
+            } else {
+                self.clear_word(word, mask);
+            }
+        }
+    }
+
+    /// Add all the values of the range.
+    pub fn insert(&mut self, range: RangeInterval<T>) {
+        if let Some((first, last)) = Self::bits(range) {
+            self.fill(first, last, true);
+        }
+    }
+
+    /// Remove all the values of the range.
+    pub fn remove(&mut self, range: RangeInterval<T>) {
+        if let Some((first, last)) = Self::bits(range) {
+            self.fill(first, last, false);
+        }
+    }
+
+    /// Test if the value is in the set.
+    pub fn contains(&self, value: T) -> bool {
+        let index = value.to_index();
+        self.words[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0
+    }
+
+    /// Test if all the values of the range are in the set, by whole words.
+    pub fn contains_range(&self, range: RangeInterval<T>) -> bool {
+        match Self::bits(range) {
+            Some((first, last)) => Self::masks(first, last).all(|(word, mask)| self.words[word] & mask == mask),
+            None => true,
+        }
+    }
+
+    /// The words of the bit array, the value `i` is the bit `i % 64` of the word `i / 64`.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Add the values of the bits set in the mask to the word.
+    pub fn set_word(&mut self, word: usize, mask: u64) {
+        self.words[word] |= mask;
+    }
+
+    /// Remove the values of the bits set in the mask from the word.
+    pub fn clear_word(&mut self, word: usize, mask: u64) {
+        self.words[word] &= !mask;
+    }
+
+    /// Remove all the values.
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// The number of values in the set.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Test if there is no value in the set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// The minimal list of sorted ranges of the values in the set, as ranges included from both sides.
+    pub fn ranges(&self) -> Vec<RangeInterval<T>> {
+        let mut ranges = Vec::new();
+        let mut start: Option<usize> = None;
+        for (i, &word) in self.words.iter().enumerate() {
+            let base = i * WORD_BITS;
+            let mut offset = 0;
+            while offset < WORD_BITS {
+                // the length of the run of the bits which do not change the state
+                let rest = word >> offset;
+                offset += match start {
+                    None => (rest.trailing_zeros() as usize).min(WORD_BITS - offset),
+                    Some(_) => (!rest).trailing_zeros() as usize,
+                };
+                if offset < WORD_BITS {
+                    start = match start {
+                        None => Some(base + offset),
+                        Some(first) => {
+                            ranges.push(Self::run(first, base + offset - 1));
+                            None
+                        }
+                    };
+                }
+            }
+        }
+        if let Some(first) = start {
+            ranges.push(Self::run(first, T::SIZE - 1));
+        }
+        ranges
+    }
+
+    fn run(first: usize, last: usize) -> RangeInterval<T> {
+        RangeInterval {
+            reverse: false,
+            start: Bound::Included(T::from_index(first)),
+            end: Bound::Included(T::from_index(last)),
+        }
+    }
+}
+
+impl<T: DenseIndex> Extend<RangeInterval<T>> for DenseRangeSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInterval<T>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|range| self.insert(range));
+    }
+}
+
+impl<T: DenseIndex> FromIterator<RangeInterval<T>> for DenseRangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInterval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: DenseIndex> From<Vec<RangeInterval<T>>> for DenseRangeSet<T> {
+    fn from(ranges: Vec<RangeInterval<T>>) -> Self {
+        ranges.into_iter().collect()
+    }
+}
+
+impl<T: DenseIndex> From<RangeInterval<T>> for DenseRangeSet<T> {
+    fn from(range: RangeInterval<T>) -> Self {
+        Some(range).into_iter().collect()
+    }
+}
+
+impl<T: DenseIndex + fmt::Debug> fmt::Debug for DenseRangeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.ranges()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{r, rs};
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = DenseRangeSet::<u16>::new();
+        assert!(set.is_empty());
+        set.insert(r!(10..20));
+        set.insert(r!(!19..=30));
+        assert_eq!(set.ranges(), vec![r!(10..=30)]);
+        assert_eq!(set.len(), 21);
+        assert!(set.contains(10));
+        assert!(!set.contains(31));
+        set.remove(r!(!15..20));
+        assert_eq!(set.ranges(), vec![r!(10..=15), r!(20..=30)]);
+        assert!(set.contains_range(r!(20..31)));
+        assert!(!set.contains_range(r!(15..=20)));
+        set.insert(r!(60..70));
+        set.insert(r!(130..=200));
+        assert_eq!(set.ranges(), vec![r!(10..=15), r!(20..=30), r!(60..=69), r!(130..=200)]);
+        set.remove(r!(..=60));
+        set.remove(r!(70..5));
+        assert_eq!(set.ranges(), vec![r!(130..=200)]);
+        // the ranges spanning several words
+        set.insert(r!(10..=300));
+        assert!(set.contains_range(r!(10..=300)));
+        assert!(set.contains_range(r!(64..=127)));
+        assert!(!set.contains_range(r!(9..=300)));
+        assert!(!set.contains_range(r!(10..=301)));
+        set.remove(r!(128..=128));
+        assert!(!set.contains_range(r!(100..=250)));
+        assert!(set.contains_range(r!(!128..=300)));
+        assert!(set.contains_range(r!(5..5)));
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut set = DenseRangeSet::<u8>::new();
+        set.insert(r!(..));
+        assert_eq!(set.len(), 256);
+        assert_eq!(set.ranges(), vec![r!(0..=255)]);
+        set.remove(r!(!0..255));
+        assert_eq!(set.ranges(), vec![r!(0..=0), r!(255..=255)]);
+        set.insert(r!(!254..));
+        set.insert(r!(5..5));
+        set.insert(r!(!5..6));
+        assert_eq!(set.ranges(), vec![r!(0..=0), r!(255..=255)]);
+        set.insert(r!(63..=64));
+        assert_eq!(set.ranges(), vec![r!(0..=0), r!(63..=64), r!(255..=255)]);
+        assert!(set.contains(64));
+        assert!(!set.contains(65));
+    }
+
+    #[test]
+    fn test_words() {
+        let mut set = DenseRangeSet::<u8>::new();
+        set.set_word(1, !0);
+        set.clear_word(1, 0xff);
+        assert_eq!(set.ranges(), vec![r!(72..=127)]);
+        assert_eq!(set.words(), &[0, !0xff, 0, 0]);
+    }
+
+    #[test]
+    fn test_import() {
+        let set: DenseRangeSet<u16> = rs![1..5, 3..=7, 100, 65000..].into();
+        assert_eq!(set.ranges(), vec![r!(1..=7), r!(100..=100), r!(65000..=65535)]);
+        assert_eq!(DenseRangeSet::from(r!(10u8..5)).ranges(), vec![r!(6..=10)]);
+    }
+}
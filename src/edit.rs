@@ -0,0 +1,192 @@
+//! Rebasing of stored byte offset ranges, like highlights or diagnostics, over a text edit which replaces
+//! a range of the text with a number of new bytes. The ranges after the edit are shifted, the ranges touching
+//! the edit are shrunk, grown or split by the policy, and the ranges which lose all their text are dropped.
+
+
+use std::ops::Bound;
+use crate::binary_result::BinaryResult;
+use crate::range_interval::RangeInterval;
+use crate::relation::RangeOrdering;
+
+/// What happens to the bounds of a range which are inside or at the edges of the edit.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EditPolicy {
+    /// Both bounds move before the new text.
+    StickLeft,
+    /// Both bounds move after the new text.
+    StickRight,
+    /// The start moves before and the end after the new text, so the range includes the new text.
+    Expand,
+    /// The start moves after and the end before the new text, so the range never includes the new text,
+    /// and the range containing the whole edit is split in two.
+    Split,
+}
+
+/// Text edit replacing the range of the text with the number of new bytes.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TextEdit {
+    pub range: RangeInterval<usize>,
+    pub inserted: usize,
+}
+
+impl TextEdit {
+    /// Create the edit replacing the range with the number of new bytes.
+    pub fn new(range: RangeInterval<usize>, inserted: usize) -> Self {
+        Self { range, inserted }
+    }
+
+    /// Create the edit inserting the number of bytes at the offset.
+    pub fn insert(offset: usize, inserted: usize) -> Self {
+        Self::new(RangeInterval::new(Bound::Included(offset), Bound::Excluded(offset)), inserted)
+    }
+
+    /// The offset after the edit, the offset inside the edit moves before or after the new text.
+    fn map(&self, (start, end): (usize, usize), offset: usize, after: bool) -> usize {
+        if offset < start {
+            offset
+        } else if offset > end {
+            (offset - (end - start)).saturating_add(self.inserted)
+        } else if after {
+            start.saturating_add(self.inserted)
+        } else {
+            start
+        }
+    }
+}
+
+impl RangeInterval<usize> {
+    /// The range after the edit, None if the range lost all its text, or two ranges if the policy is `Split`
+    /// and the range contains the whole edit. The ranges are returned with included start and excluded end,
+    /// the unbounded ends stay unbounded, and the empty range, like a cursor, is kept.
+    pub fn apply_edit(&self, edit: &TextEdit, policy: EditPolicy) -> BinaryResult<usize> {
+        let replaced = edit.range.offsets(usize::MAX);
+        let replaced = (replaced.start, replaced.end);
+        let offsets = self.offsets(usize::MAX);
+        let (start, end) = (offsets.start, offsets.end);
+        let (start_after, end_after) = match policy {
+            EditPolicy::StickLeft => (false, false),
+            EditPolicy::StickRight => (true, true),
+            EditPolicy::Expand => (false, true),
+            EditPolicy::Split => (true, false),
+        };
+
+        // the start or the end None is unbounded
+        let range = |start: Option<usize>, end: Option<usize>| {
+            let start = start.map_or(Bound::Unbounded, Bound::Included);
+            let end = end.map_or(Bound::Unbounded, Bound::Excluded);
+            let reverse = self.reverse && start != Bound::Unbounded && end != Bound::Unbounded;
+            RangeInterval { reverse, start, end }
+        };
+        let unbounded_start = self.start == Bound::Unbounded;
+        let unbounded = self.end == Bound::Unbounded;
+        let bounded_start = |start: usize| Some(start).filter(|_| !unbounded_start);
+
+        if start == end {
+            let point = edit.map(replaced, start, start_after);
+            return BinaryResult::One(range(bounded_start(point), Some(point)));
+        }
+
+        // the range containing the whole edit, with some of its own text on both sides
+        let contains_edit = match self.relation(&edit.range) {
+            RangeOrdering::Over => start < replaced.0 && replaced.1 < end,
+            RangeOrdering::Empty => start < replaced.0 && replaced.0 < end,
+            _ => false,
+        };
+        if policy == EditPolicy::Split && contains_edit {
+            let after = replaced.0.saturating_add(edit.inserted);
+            let end = Some(edit.map(replaced, end, end_after)).filter(|_| !unbounded);
+            return BinaryResult::Two(range(bounded_start(start), Some(replaced.0)), range(Some(after), end));
+        }
+
+        let new_start = edit.map(replaced, start, start_after);
+        let new_end = if unbounded { end } else { edit.map(replaced, end, end_after) };
+        if new_start < new_end {
+            BinaryResult::One(range(bounded_start(new_start), Some(new_end).filter(|_| !unbounded)))
+        } else {
+            BinaryResult::None
+        }
+    }
+}
+
+/// Apply the edit to all the ranges, keeping their order, the dropped ranges are removed and the split ranges
+/// are replaced by both parts.
+pub fn apply_edit_all(
+    ranges: &[RangeInterval<usize>],
+    edit: &TextEdit,
+    policy: EditPolicy,
+) -> Vec<RangeInterval<usize>> {
+    ranges.iter()
+        .flat_map(|range| range.apply_edit(edit, policy).into_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_outside() {
+        // replace 10..15 with 2 bytes
+        let edit = TextEdit::new(r!(10..15), 2);
+        for policy in [EditPolicy::StickLeft, EditPolicy::StickRight, EditPolicy::Expand, EditPolicy::Split] {
+            assert_eq!(r!(2..8).apply_edit(&edit, policy), BinaryResult::One(r!(2..8)));
+            assert_eq!(r!(20..=30).apply_edit(&edit, policy), BinaryResult::One(r!(17..28)));
+            assert_eq!(r!(!15..20).apply_edit(&edit, policy), BinaryResult::One(r!(13..17)));
+            assert_eq!(r!(30..20).apply_edit(&edit, policy), BinaryResult::One(r!(18..28).reversed()));
+            assert_eq!(r!(20..).apply_edit(&edit, policy), BinaryResult::One(r!(17..)));
+        }
+    }
+
+    #[test]
+    fn test_policies() {
+        let edit = TextEdit::new(r!(10..15), 2);
+        assert_eq!(r!(5..12).apply_edit(&edit, EditPolicy::StickLeft), BinaryResult::One(r!(5..10)));
+        assert_eq!(r!(5..12).apply_edit(&edit, EditPolicy::StickRight), BinaryResult::One(r!(5..12)));
+        assert_eq!(r!(5..12).apply_edit(&edit, EditPolicy::Expand), BinaryResult::One(r!(5..12)));
+        assert_eq!(r!(5..12).apply_edit(&edit, EditPolicy::Split), BinaryResult::One(r!(5..10)));
+
+        assert_eq!(r!(12..20).apply_edit(&edit, EditPolicy::StickLeft), BinaryResult::One(r!(10..17)));
+        assert_eq!(r!(12..20).apply_edit(&edit, EditPolicy::StickRight), BinaryResult::One(r!(12..17)));
+        assert_eq!(r!(12..20).apply_edit(&edit, EditPolicy::Expand), BinaryResult::One(r!(10..17)));
+        assert_eq!(r!(12..20).apply_edit(&edit, EditPolicy::Split), BinaryResult::One(r!(12..17)));
+
+        assert_eq!(r!(11..14).apply_edit(&edit, EditPolicy::StickLeft), BinaryResult::None);
+        assert_eq!(r!(11..14).apply_edit(&edit, EditPolicy::StickRight), BinaryResult::None);
+        assert_eq!(r!(11..14).apply_edit(&edit, EditPolicy::Expand), BinaryResult::One(r!(10..12)));
+        assert_eq!(r!(11..14).apply_edit(&edit, EditPolicy::Split), BinaryResult::None);
+
+        assert_eq!(r!(5..20).apply_edit(&edit, EditPolicy::StickLeft), BinaryResult::One(r!(5..17)));
+        assert_eq!(r!(5..20).apply_edit(&edit, EditPolicy::Split), BinaryResult::Two(r!(5..10), r!(12..17)));
+        assert_eq!(
+            r!(20..5).apply_edit(&edit, EditPolicy::Split),
+            BinaryResult::Two(r!(6..10).reversed(), r!(12..18).reversed())
+        );
+        assert_eq!(r!(5..).apply_edit(&edit, EditPolicy::Split), BinaryResult::Two(r!(5..10), r!(12..)));
+    }
+
+    #[test]
+    fn test_insert() {
+        let edit = TextEdit::insert(10, 3);
+        assert_eq!(r!(5..10).apply_edit(&edit, EditPolicy::StickLeft), BinaryResult::One(r!(5..10)));
+        assert_eq!(r!(5..10).apply_edit(&edit, EditPolicy::Expand), BinaryResult::One(r!(5..13)));
+        assert_eq!(r!(10..15).apply_edit(&edit, EditPolicy::StickLeft), BinaryResult::One(r!(10..18)));
+        assert_eq!(r!(10..15).apply_edit(&edit, EditPolicy::StickRight), BinaryResult::One(r!(13..18)));
+        assert_eq!(r!(5..15).apply_edit(&edit, EditPolicy::Split), BinaryResult::Two(r!(5..10), r!(13..18)));
+        assert_eq!(r!(5..15).apply_edit(&edit, EditPolicy::StickLeft), BinaryResult::One(r!(5..18)));
+        // the cursor is kept
+        assert_eq!(r!(10..10).apply_edit(&edit, EditPolicy::StickLeft), BinaryResult::One(r!(10..10)));
+        assert_eq!(r!(10..10).apply_edit(&edit, EditPolicy::StickRight), BinaryResult::One(r!(13..13)));
+        assert_eq!(r!(..10).apply_edit(&TextEdit::insert(0, 3), EditPolicy::StickRight), BinaryResult::One(r!(..13)));
+        assert_eq!(r!(..10).apply_edit(&TextEdit::new(r!(2..4), 0), EditPolicy::Split), BinaryResult::Two(r!(..2), r!(2..8)));
+        assert_eq!(r!(..).apply_edit(&TextEdit::insert(4, 1), EditPolicy::Split), BinaryResult::Two(r!(..4), r!(5..)));
+    }
+
+    #[test]
+    fn test_bulk() {
+        let ranges = vec![r!(0..4), r!(3..8), r!(6..7), r!(9..12)];
+        let edit = TextEdit::new(r!(5..=7), 0);
+        assert_eq!(apply_edit_all(&ranges, &edit, EditPolicy::Split), vec![r!(0..4), r!(3..5), r!(6..9)]);
+    }
+}
@@ -0,0 +1,88 @@
+//! The smallest range covering two ranges (hull), and the range strictly between two disjoint ranges (gap).
+
+
+use std::cmp::Ordering;
+use std::ops::Bound;
+use crate::bounds::{cmp_cut, max_cut, min_cut};
+use crate::range_interval::RangeInterval;
+
+impl<T: PartialOrd + Clone> RangeInterval<T> {
+    /// Return the smallest range which covers both ranges, including everything between them.
+    /// An empty range does not extend the hull, the hull with it is the other range unchanged.
+    /// Otherwise the result keeps the direction of this range.
+    pub fn hull(self, other: Self) -> Self {
+        if other.is_void() {
+            return self;
+        }
+        if self.is_void() {
+            return other;
+        }
+        let start = min_cut(self.start_cut(), other.start_cut()).to_start();
+        let end = max_cut(self.end_cut(), other.end_cut()).to_end();
+        let reverse = self.reverse && !matches!((&start, &end), (Bound::Unbounded, _) | (_, Bound::Unbounded));
+        RangeInterval { reverse, start, end }
+    }
+
+    /// Same as `hull`, the span of both ranges.
+    pub fn span(self, other: Self) -> Self {
+        self.hull(other)
+    }
+
+    /// Return the range strictly between two disjoint ranges, the bounds of the gap are inverted from the bounds
+    /// of the ranges, so the gap of `<1..3>` and `<7..9>` is `(3..7)`.
+    /// Return None if the ranges overlap, touch each other or one of them is empty.
+    /// The result keeps the direction of this range.
+    pub fn gap(self, other: Self) -> Option<Self> {
+        if self.is_void() || other.is_void() {
+            return None;
+        }
+        let (start, end) = if cmp_cut(&self.end_cut(), &other.start_cut()) == Ordering::Less {
+            (self.end_cut().to_start(), other.start_cut().to_end())
+        } else if cmp_cut(&other.end_cut(), &self.start_cut()) == Ordering::Less {
+            (other.end_cut().to_start(), self.start_cut().to_end())
+        } else {
+            return None;
+        };
+        Some(RangeInterval { reverse: self.reverse, start, end })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::r;
+
+    #[test]
+    fn test_hull() {
+        assert_eq!(r!(1..3).hull(r!(7..=9)), r!(1..=9));
+        assert_eq!(r!(7..=9).hull(r!(1..3)), r!(1..=9));
+        assert_eq!(r!(!1..3).hull(r!(1..2)), r!(1..3));
+        assert_eq!(r!(!1..3).hull(r!(!1..=3)), r!(!1..=3));
+        assert_eq!(r!(2..5).hull(r!(3..4)), r!(2..5));
+        assert_eq!(r!(2..5).hull(r!(..4)), r!(..5));
+        assert_eq!(r!(2..5).hull(r!(!4..)), r!(2..));
+        assert_eq!(r!(2..5).hull(r!(..)), r!(..));
+        assert_eq!(r!(..1).hull(r!(!5..)), r!(..));
+        assert_eq!(r!(1..3).hull(r!(5..5)), r!(1..3));
+        assert_eq!(r!(5..5).hull(r!(1..3)), r!(1..3));
+        assert_eq!(r!(5..5).hull(r!(3..1)), r!(3..1));
+        assert_eq!(r!(5..1).hull(r!(!3..3)), r!(5..1));
+        assert_eq!(r!(10..1).hull(r!(12..=15)), r!(15..1));
+        assert_eq!(r!(1..3).span(r!(7..=9)), r!(1..=9));
+    }
+
+    #[test]
+    fn test_gap() {
+        assert_eq!(r!(1..=3).gap(r!(7..=9)), Some(r!(!3..7)));
+        assert_eq!(r!(7..=9).gap(r!(1..=3)), Some(r!(!3..7)));
+        assert_eq!(r!(1..3).gap(r!(!7..=9)), Some(r!(3..=7)));
+        assert_eq!(r!(1..3).gap(r!(!3..=9)), Some(r!(3..=3)));
+        assert_eq!(r!(1..3).gap(r!(3..=9)), None);
+        assert_eq!(r!(1..=3).gap(r!(!3..=9)), None);
+        assert_eq!(r!(1..=3).gap(r!(3..=9)), None);
+        assert_eq!(r!(1..5).gap(r!(3..=9)), None);
+        assert_eq!(r!(..=3).gap(r!(!7..)), Some(r!(!3..=7)));
+        assert_eq!(r!(..).gap(r!(7..9)), None);
+        assert_eq!(r!(1..1).gap(r!(7..9)), None);
+        assert_eq!(r!(1.0..2.5).gap(r!(3.0..4.0)), Some(r!(2.5..3.0)));
+    }
+}
@@ -0,0 +1,219 @@
+//! Allocation of integer identifiers with recycling. Released identifiers are kept as coalesced ranges, so the
+//! memory is proportional to the number of gaps between the identifiers in use, not to the number of identifiers.
+
+
+use std::ops::Bound;
+use crate::allocator::{AllocError, FreeList};
+use crate::range_interval::RangeInterval;
+use crate::successor::Successor;
+
+/// Errors of the identifier allocation.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum IdError {
+    /// There are no more identifiers.
+    Exhausted,
+    /// The released identifier is not acquired.
+    NotAcquired,
+    /// The released identifier is reserved.
+    Reserved,
+    /// The requested count of identifiers is zero.
+    InvalidCount,
+}
+
+/// Allocator of identifiers, which hands out the lowest released identifier first, and new identifiers after
+/// the highest one handed out. The reserved identifiers are never handed out.
+#[derive(Debug, Clone)]
+pub struct IdAllocator<T> {
+    free: FreeList<T>,
+    /// The lowest identifier, the identifiers below it are never handed out.
+    first: T,
+    /// The lowest identifier which was never handed out, None if there is no more.
+    next: Option<T>,
+    reserved: Vec<RangeInterval<T>>,
+}
+
+impl<T: PartialOrd + Successor + Clone> IdAllocator<T> {
+    /// Create the allocator handing out identifiers from the first one.
+    pub fn new(first: T) -> Self {
+        Self::with_reserved(first, Vec::new())
+    }
+
+    /// Create the allocator handing out identifiers from the first one, and skipping the reserved ranges.
+    pub fn with_reserved(first: T, reserved: impl IntoIterator<Item = RangeInterval<T>>) -> Self {
+        Self { free: FreeList::new(None), first: first.clone(), next: Some(first), reserved: reserved.into_iter().collect() }
+    }
+
+    /// The released identifiers which can be handed out again.
+    pub fn free_ranges(&self) -> &[RangeInterval<T>] {
+        self.free.ranges()
+    }
+
+    /// Hand out an identifier.
+    pub fn acquire(&mut self) -> Result<T, IdError> {
+        self.acquire_range(1).map(|range| match range.start {
+            Bound::Included(id) => id,
+            _ => unreachable!("acquired range starts with included identifier"),
+        })
+    }
+
+    /// Hand out the count of consecutive identifiers, as a range with included start and excluded end.
+    /// The count must not be zero.
+    pub fn acquire_range(&mut self, count: usize) -> Result<RangeInterval<T>, IdError> {
+        if count == 0 {
+            return Err(IdError::InvalidCount);
+        }
+        let released = self.free.ranges().iter().find_map(|free| match &free.start {
+            Bound::Included(start) => start.advance(count - 1)
+                .filter(|last| free.contains(last.clone()))
+                .map(|last| RangeInterval { reverse: false, start: Bound::Included(start.clone()), end: Bound::Included(last) }),
+            _ => None,
+        });
+        if let Some(range) = released {
+            return self.free.remove(range).map_err(|_| IdError::Exhausted);
+        }
+
+        let mut start = self.next.clone().ok_or(IdError::Exhausted)?;
+        loop {
+            let last = start.advance(count - 1).ok_or(IdError::Exhausted)?;
+            let range = RangeInterval { reverse: false, start: Bound::Included(start.clone()), end: Bound::Included(last.clone()) };
+            match self.reserved.iter().find(|reserved| reserved.overlaps(&range)) {
+                None => {
+                    self.next = last.next();
+                    return range.half_open().ok_or(IdError::Exhausted);
+                }
+                Some(reserved) => {
+                    // the identifiers before the reserved range are kept for later
+                    let inside = if reserved.contains(start.clone()) {
+                        start.clone()
+                    } else {
+                        match reserved.clone().closed().map(|closed| closed.start) {
+                            Some(Bound::Included(first)) => {
+                                let skipped = RangeInterval { reverse: false, start: Bound::Included(start), end: Bound::Excluded(first.clone()) };
+                                self.free.insert(skipped).map_err(|_| IdError::Exhausted)?;
+                                first
+                            }
+                            _ => start,
+                        }
+                    };
+                    start = reserved.avoid(inside, true).ok_or(IdError::Exhausted)?;
+                    self.next = Some(start.clone());
+                }
+            }
+        }
+    }
+
+    /// Return the identifier, so it can be handed out again.
+    pub fn release(&mut self, id: T) -> Result<(), IdError> {
+        if self.reserved.iter().any(|reserved| reserved.contains(id.clone())) {
+            return Err(IdError::Reserved);
+        }
+        if id < self.first || matches!(&self.next, Some(next) if id >= *next) {
+            return Err(IdError::NotAcquired);
+        }
+        self.free.insert(RangeInterval { reverse: false, start: Bound::Included(id.clone()), end: Bound::Included(id) })
+            .map_err(|error| match error {
+                AllocError::DoubleFree | AllocError::OverlappingFree => IdError::NotAcquired,
+                _ => IdError::Exhausted,
+            })?;
+        // the released identifiers just below the next one are forgotten, and the next one is moved down instead
+        if let Some(last) = self.free.ranges().last().cloned() {
+            let below_next = match (&last.end, &self.next) {
+                (Bound::Excluded(end), Some(next)) => end == next,
+                (Bound::Included(_), None) => true,
+                _ => false,
+            };
+            if let (true, Bound::Included(start)) = (below_next, &last.start) {
+                self.next = Some(start.clone());
+                self.free.remove(last).map_err(|_| IdError::NotAcquired)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_acquire_release() {
+        let mut ids = IdAllocator::new(0u32);
+        assert_eq!(ids.acquire(), Ok(0));
+        assert_eq!(ids.acquire(), Ok(1));
+        assert_eq!(ids.acquire(), Ok(2));
+        assert_eq!(ids.acquire(), Ok(3));
+        assert_eq!(ids.release(1), Ok(()));
+        assert_eq!(ids.release(2), Ok(()));
+        assert_eq!(ids.free_ranges(), &[r!(1..3)]);
+        assert_eq!(ids.release(2), Err(IdError::NotAcquired));
+        assert_eq!(ids.release(4), Err(IdError::NotAcquired));
+        assert_eq!(ids.acquire(), Ok(1));
+        assert_eq!(ids.free_ranges(), &[r!(2..3)]);
+        assert_eq!(ids.release(3), Ok(()));
+        // 2 and 3 are below the next identifier, so they are not kept
+        assert_eq!(ids.free_ranges(), &[]);
+        assert_eq!(ids.acquire(), Ok(2));
+        assert_eq!(ids.acquire(), Ok(3));
+        assert_eq!(ids.acquire(), Ok(4));
+    }
+
+    #[test]
+    fn test_memory() {
+        let mut ids = IdAllocator::new(0u32);
+        for _ in 0..1000 {
+            ids.acquire().unwrap();
+        }
+        for id in (0..1000).filter(|id| id % 100 != 0) {
+            ids.release(id).unwrap();
+        }
+        assert_eq!(ids.free_ranges().len(), 9);
+        assert_eq!(ids.free_ranges()[0], r!(1..100));
+        assert_eq!(ids.acquire(), Ok(1));
+    }
+
+    #[test]
+    fn test_acquire_range() {
+        let mut ids = IdAllocator::new(10u8);
+        assert_eq!(ids.acquire_range(5), Ok(r!(10..15)));
+        assert_eq!(ids.acquire_range(5), Ok(r!(15..20)));
+        assert_eq!(ids.release(11), Ok(()));
+        assert_eq!(ids.release(12), Ok(()));
+        assert_eq!(ids.acquire_range(3), Ok(r!(20..23)));
+        assert_eq!(ids.acquire_range(2), Ok(r!(11..13)));
+        assert_eq!(ids.acquire_range(0), Err(IdError::InvalidCount));
+        assert_eq!(ids.release(3), Err(IdError::NotAcquired));
+        assert_eq!(ids.acquire_range(240), Err(IdError::Exhausted));
+        assert_eq!(ids.acquire_range(233), Ok(r!(23..=255)));
+        assert_eq!(ids.acquire(), Err(IdError::Exhausted));
+        assert_eq!(ids.release(255), Ok(()));
+        assert_eq!(ids.free_ranges(), &[]);
+        assert_eq!(ids.acquire(), Ok(255));
+        assert_eq!(ids.acquire_range(0), Err(IdError::InvalidCount));
+
+        // the end of a large range is not found by stepping through it
+        let mut ids = IdAllocator::new(0u64);
+        assert_eq!(ids.acquire_range(1 << 40), Ok(r!(0..1 << 40)));
+        assert_eq!(ids.acquire_range(usize::MAX), Err(IdError::Exhausted));
+    }
+
+    #[test]
+    fn test_reserved() {
+        let mut ids = IdAllocator::with_reserved(0u16, vec![r!(2..=3), r!(!5..7)]);
+        assert_eq!(ids.acquire(), Ok(0));
+        assert_eq!(ids.acquire(), Ok(1));
+        assert_eq!(ids.acquire(), Ok(4));
+        assert_eq!(ids.acquire(), Ok(5));
+        assert_eq!(ids.acquire(), Ok(7));
+        assert_eq!(ids.release(6), Err(IdError::Reserved));
+        assert_eq!(ids.release(1), Ok(()));
+
+        let mut ids = IdAllocator::with_reserved(0u16, vec![r!(3..=4)]);
+        assert_eq!(ids.acquire(), Ok(0));
+        assert_eq!(ids.acquire_range(3), Ok(r!(5..8)));
+        assert_eq!(ids.free_ranges(), &[r!(1..3)]);
+        assert_eq!(ids.acquire_range(2), Ok(r!(1..3)));
+        assert_eq!(ids.acquire(), Ok(8));
+    }
+}
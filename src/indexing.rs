@@ -0,0 +1,183 @@
+//! Indexing of slices, strings and vectors with the ranges, including the ranges with excluded start which
+//! the standard ranges cannot express. The unbounded ends are the ends of the slice, and the reversed ranges
+//! give the reversed iterators.
+
+
+use std::ops::Range;
+use crate::range_interval::RangeInterval;
+
+/// Errors of the indexing with a range.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum IndexError {
+    /// The first offset of the range is after the end of the slice of the length.
+    StartOutOfBounds { start: usize, len: usize },
+    /// The offset after the range is after the end of the slice of the length.
+    EndOutOfBounds { end: usize, len: usize },
+    /// The offset of the range is inside a character of the string.
+    NotCharBoundary(usize),
+}
+
+/// The half open offsets of the range in the slice of the length.
+fn checked_offsets(range: &RangeInterval<usize>, len: usize) -> Result<Range<usize>, IndexError> {
+    let offsets = range.offsets(len);
+    if offsets.start > len {
+        Err(IndexError::StartOutOfBounds { start: offsets.start, len })
+    } else if offsets.end > len {
+        Err(IndexError::EndOutOfBounds { end: offsets.end, len })
+    } else {
+        Ok(offsets)
+    }
+}
+
+/// Iterator in the direction of the range, from the end to the start for the reversed range.
+#[derive(Debug, Clone)]
+pub struct IntervalIter<I> {
+    inner: I,
+    reverse: bool,
+}
+
+impl<I: DoubleEndedIterator> Iterator for IntervalIter<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reverse { self.inner.next_back() } else { self.inner.next() }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator> DoubleEndedIterator for IntervalIter<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.reverse { self.inner.next() } else { self.inner.next_back() }
+    }
+}
+
+impl<I: DoubleEndedIterator + ExactSizeIterator> ExactSizeIterator for IntervalIter<I> {}
+
+/// Indexing of slices with the ranges.
+pub trait SliceRangeExt<T> {
+    /// The part of the slice in the range.
+    fn get_interval(&self, range: &RangeInterval<usize>) -> Result<&[T], IndexError>;
+
+    /// The mutable part of the slice in the range.
+    fn get_interval_mut(&mut self, range: &RangeInterval<usize>) -> Result<&mut [T], IndexError>;
+
+    /// The elements in the range, in the direction of the range.
+    fn iter_interval(&self, range: &RangeInterval<usize>) -> Result<IntervalIter<std::slice::Iter<'_, T>>, IndexError>;
+}
+
+impl<T> SliceRangeExt<T> for [T] {
+    fn get_interval(&self, range: &RangeInterval<usize>) -> Result<&[T], IndexError> {
+        Ok(&self[checked_offsets(range, self.len())?])
+    }
+
+    fn get_interval_mut(&mut self, range: &RangeInterval<usize>) -> Result<&mut [T], IndexError> {
+        let offsets = checked_offsets(range, self.len())?;
+        Ok(&mut self[offsets])
+    }
+
+    fn iter_interval(&self, range: &RangeInterval<usize>) -> Result<IntervalIter<std::slice::Iter<'_, T>>, IndexError> {
+        Ok(IntervalIter { inner: self.get_interval(range)?.iter(), reverse: range.reverse })
+    }
+}
+
+/// Indexing of strings with the byte offset ranges.
+pub trait StrRangeExt {
+    /// The part of the string in the range.
+    fn get_interval(&self, range: &RangeInterval<usize>) -> Result<&str, IndexError>;
+
+    /// The mutable part of the string in the range.
+    fn get_interval_mut(&mut self, range: &RangeInterval<usize>) -> Result<&mut str, IndexError>;
+
+    /// The characters in the range, in the direction of the range.
+    fn chars_interval(&self, range: &RangeInterval<usize>) -> Result<IntervalIter<std::str::Chars<'_>>, IndexError>;
+}
+
+/// The offsets of the range in the string, which are both at the character boundaries.
+fn str_offsets(s: &str, range: &RangeInterval<usize>) -> Result<Range<usize>, IndexError> {
+    let offsets = checked_offsets(range, s.len())?;
+    match [offsets.start, offsets.end].iter().copied().find(|&offset| !s.is_char_boundary(offset)) {
+        Some(offset) => Err(IndexError::NotCharBoundary(offset)),
+        None => Ok(offsets),
+    }
+}
+
+impl StrRangeExt for str {
+    fn get_interval(&self, range: &RangeInterval<usize>) -> Result<&str, IndexError> {
+        Ok(&self[str_offsets(self, range)?])
+    }
+
+    fn get_interval_mut(&mut self, range: &RangeInterval<usize>) -> Result<&mut str, IndexError> {
+        let offsets = str_offsets(self, range)?;
+        Ok(&mut self[offsets])
+    }
+
+    fn chars_interval(&self, range: &RangeInterval<usize>) -> Result<IntervalIter<std::str::Chars<'_>>, IndexError> {
+        Ok(IntervalIter { inner: self.get_interval(range)?.chars(), reverse: range.reverse })
+    }
+}
+
+/// Removal of the elements of vectors in the ranges.
+pub trait VecRangeExt<T> {
+    /// Remove the elements in the range and iterate them in the direction of the range. As with `Vec::drain`,
+    /// the elements are removed even if the iterator is not consumed.
+    fn drain_interval(&mut self, range: &RangeInterval<usize>) -> Result<IntervalIter<std::vec::Drain<'_, T>>, IndexError>;
+}
+
+impl<T> VecRangeExt<T> for Vec<T> {
+    fn drain_interval(&mut self, range: &RangeInterval<usize>) -> Result<IntervalIter<std::vec::Drain<'_, T>>, IndexError> {
+        let offsets = checked_offsets(range, self.len())?;
+        Ok(IntervalIter { inner: self.drain(offsets), reverse: range.reverse })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_slice() {
+        let values = [0, 1, 2, 3, 4, 5];
+        assert_eq!(values.get_interval(&r!(!1..4)), Ok(&values[2..4]));
+        assert_eq!(values.get_interval(&r!(2..=3)), Ok(&values[2..4]));
+        assert_eq!(values.get_interval(&r!(..)), Ok(&values[..]));
+        assert_eq!(values.get_interval(&r!(4..)), Ok(&values[4..]));
+        assert_eq!(values.get_interval(&r!(6..)), Ok(&values[6..]));
+        assert_eq!(values.get_interval(&r!(..=6)), Err(IndexError::EndOutOfBounds { end: 7, len: 6 }));
+        assert_eq!(values.get_interval(&r!(!6..)), Err(IndexError::StartOutOfBounds { start: 7, len: 6 }));
+        assert_eq!(values.get_interval(&r!(!3..=3)), Ok(&values[4..4]));
+        assert_eq!(values.iter_interval(&r!(4..1)).unwrap().copied().collect::<Vec<_>>(), vec![4, 3, 2]);
+        assert_eq!(values.iter_interval(&r!(1..4)).unwrap().rev().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+        assert_eq!(values.iter_interval(&r!(4..1)).unwrap().len(), 3);
+
+        let mut values = values;
+        values.get_interval_mut(&r!(!0..=1)).unwrap()[0] = 10;
+        assert_eq!(values, [0, 10, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_str() {
+        let mut s = "héllo".to_string();
+        assert_eq!(s.get_interval(&r!(!0..3)), Ok("é"));
+        assert_eq!(s.get_interval(&r!(0..2)), Err(IndexError::NotCharBoundary(2)));
+        assert_eq!(s.get_interval(&r!(3..)), Ok("llo"));
+        assert_eq!(s.chars_interval(&r!(5..0)).unwrap().collect::<String>(), "ollé");
+        s.get_interval_mut(&r!(3..=4)).unwrap().make_ascii_uppercase();
+        assert_eq!(s, "héLLo");
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut values = vec![0, 1, 2, 3, 4, 5];
+        assert_eq!(values.drain_interval(&r!(5..1)).unwrap().collect::<Vec<_>>(), vec![5, 4, 3, 2]);
+        assert_eq!(values, vec![0, 1]);
+        assert_eq!(values.drain_interval(&r!(!0..)).unwrap().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(values, vec![0]);
+        assert!(values.drain_interval(&r!(0..2)).is_err());
+        assert_eq!(values, vec![0]);
+    }
+}
@@ -0,0 +1,177 @@
+//! Linear interpolation in ranges of floating point values, for example to map the values to the positions
+//! on a chart. The range goes from its start to its end, so the reversed range `r!(10.0..0.0)` maps 0 to 10
+//! and 1 to 0. The excluded ends are still the ends of the mapping, only the saturating and wrapping variants
+//! use them to decide which values are outside of the range.
+
+
+use std::ops::{Add, Bound, Div, Mul, Sub};
+use crate::arithmetic::Arithmetic;
+use crate::range_interval::RangeInterval;
+
+/// Floating point values which can be interpolated. The trait is not re-exported at the crate root, so it does
+/// not clash with the `Float` of other crates.
+pub trait Float:
+    Arithmetic + PartialOrd + Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self>
+{
+    /// The one value
+    fn one() -> Self;
+
+    /// The largest integral value not greater than the value
+    fn floor(self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($t: ty) => {
+        impl Float for $t {
+            fn one() -> Self {
+                1.0
+            }
+            fn floor(self) -> Self {
+                <$t>::floor(self)
+            }
+        }
+    }
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+/// Errors of the interpolation.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum LerpError {
+    /// The range has an unbounded end.
+    Unbounded,
+    /// The range starts and ends at the same value, so there is no position of a value in it.
+    ZeroWidth,
+}
+
+impl<T: Float> RangeInterval<T> {
+    /// The values of the ends in the direction of the range.
+    fn lerp_ends(&self) -> Result<(T, T), LerpError> {
+        let start = match self.start {
+            Bound::Included(v) | Bound::Excluded(v) => v,
+            Bound::Unbounded => return Err(LerpError::Unbounded),
+        };
+        let end = match self.end {
+            Bound::Included(v) | Bound::Excluded(v) => v,
+            Bound::Unbounded => return Err(LerpError::Unbounded),
+        };
+        Ok(if self.reverse { (end, start) } else { (start, end) })
+    }
+
+    /// The value at the position, 0 is the start and 1 is the end of the range. The positions outside of `0..=1`
+    /// extrapolate the range.
+    pub fn lerp(&self, t: T) -> Result<T, LerpError> {
+        let (from, to) = self.lerp_ends()?;
+        Ok(from + (to - from) * t)
+    }
+
+    /// The value at the position clamped to `0..=1`.
+    pub fn lerp_saturating(&self, t: T) -> Result<T, LerpError> {
+        self.lerp(clamp(t))
+    }
+
+    /// The value at the fractional part of the position, so the positions repeat the range.
+    pub fn lerp_wrapping(&self, t: T) -> Result<T, LerpError> {
+        self.lerp(t - t.floor())
+    }
+
+    /// The position of the value, the inverse of `lerp`.
+    pub fn inverse_lerp(&self, value: T) -> Result<T, LerpError> {
+        let (from, to) = self.lerp_ends()?;
+        if from == to {
+            return Err(LerpError::ZeroWidth);
+        }
+        Ok((value - from) / (to - from))
+    }
+
+    /// The position of the value, the values outside of the range are at the closer end. The positions are
+    /// clamped to `0..=1`, so an excluded end is reached like an included one.
+    pub fn inverse_lerp_saturating(&self, value: T) -> Result<T, LerpError> {
+        Ok(clamp(self.inverse_lerp(value)?))
+    }
+
+    /// The position of the value, the values outside of the range wrap around to the range like the angles.
+    pub fn inverse_lerp_wrapping(&self, value: T) -> Result<T, LerpError> {
+        let t = self.inverse_lerp(value)?;
+        Ok(if self.contains(value) { t } else { t - t.floor() })
+    }
+
+    /// The value in the other range at the position of the value in this range.
+    pub fn remap(&self, value: T, to: &Self) -> Result<T, LerpError> {
+        to.lerp(self.inverse_lerp(value)?)
+    }
+
+    /// The value in the other range at the saturated position of the value in this range.
+    pub fn remap_saturating(&self, value: T, to: &Self) -> Result<T, LerpError> {
+        to.lerp(self.inverse_lerp_saturating(value)?)
+    }
+
+    /// The value in the other range at the wrapped position of the value in this range.
+    pub fn remap_wrapping(&self, value: T, to: &Self) -> Result<T, LerpError> {
+        to.lerp(self.inverse_lerp_wrapping(value)?)
+    }
+}
+
+fn clamp<T: Float>(t: T) -> T {
+    if t < T::zero() {
+        T::zero()
+    } else if t > T::one() {
+        T::one()
+    } else {
+        t
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_lerp() {
+        let range = r!(10.0..20.0);
+        assert_eq!(range.lerp(0.0), Ok(10.0));
+        assert_eq!(range.lerp(0.25), Ok(12.5));
+        assert_eq!(range.lerp(1.5), Ok(25.0));
+        assert_eq!(range.lerp_saturating(1.5), Ok(20.0));
+        assert_eq!(range.lerp_saturating(-1.0), Ok(10.0));
+        assert_eq!(range.lerp_wrapping(1.5), Ok(15.0));
+        assert_eq!(range.lerp_wrapping(-0.25), Ok(17.5));
+        assert_eq!(r!(20.0..10.0).lerp(0.25), Ok(17.5));
+        assert_eq!(r!(10.0..).lerp(0.5), Err(LerpError::Unbounded));
+    }
+
+    #[test]
+    fn test_inverse_lerp() {
+        let range = r!(10.0..20.0);
+        assert_eq!(range.inverse_lerp(15.0), Ok(0.5));
+        assert_eq!(range.inverse_lerp(5.0), Ok(-0.5));
+        assert_eq!(range.inverse_lerp_saturating(5.0), Ok(0.0));
+        assert_eq!(range.inverse_lerp_saturating(20.0), Ok(1.0));
+        assert_eq!(range.inverse_lerp_wrapping(25.0), Ok(0.5));
+        assert_eq!(range.inverse_lerp_wrapping(20.0), Ok(0.0));
+        assert_eq!(r!(10.0..=20.0).inverse_lerp_wrapping(20.0), Ok(1.0));
+        assert_eq!(r!(20.0..10.0).inverse_lerp(12.5), Ok(0.75));
+        assert_eq!(r!(10.0..=10.0).inverse_lerp(10.0), Err(LerpError::ZeroWidth));
+        assert_eq!(r!(..10.0).inverse_lerp(5.0), Err(LerpError::Unbounded));
+    }
+
+    #[test]
+    fn test_remap() {
+        let celsius = r!(0.0f64..=100.0);
+        let fahrenheit = r!(32.0..=212.0);
+        assert!((celsius.remap(37.0, &fahrenheit).unwrap() - 98.6).abs() < 1e-9);
+        assert_eq!(celsius.remap(-40.0, &fahrenheit), Ok(-40.0));
+        assert_eq!(celsius.remap_saturating(-40.0, &fahrenheit), Ok(32.0));
+        // the screen coordinates grow downwards
+        let screen = r!(480.0..0.0);
+        assert_eq!(r!(0.0..=1.0).remap(0.25, &screen), Ok(360.0));
+        let degrees = r!(0.0..360.0);
+        let turns = r!(0.0f32..1.0);
+        assert_eq!(degrees.remap_wrapping(450.0, &turns), Ok(0.25));
+        assert_eq!(degrees.remap_wrapping(-90.0, &turns), Ok(0.75));
+        assert_eq!(degrees.remap(90.0, &r!(0.0..)), Err(LerpError::Unbounded));
+    }
+}
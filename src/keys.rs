@@ -0,0 +1,151 @@
+//! Lexicographic key ranges of byte strings and strings, for the prefix scans and the separators of the keys
+//! in a storage engine. The ranges can be used directly with `BTreeMap::range`.
+
+
+use std::ops::Bound;
+use crate::range_interval::RangeInterval;
+
+impl RangeInterval<Vec<u8>> {
+    /// The range of all the keys starting with the prefix, `prefix(b"user/")` is `<"user/".."user0")`.
+    /// The end is unbounded if there is no key after all the keys with the prefix.
+    pub fn prefix(prefix: &[u8]) -> Self {
+        let mut end = prefix.to_vec();
+        while let Some(last) = end.pop() {
+            if last < u8::MAX {
+                end.push(last + 1);
+                return RangeInterval::new(Bound::Included(prefix.to_vec()), Bound::Excluded(end));
+            }
+        }
+        RangeInterval::new(Bound::Included(prefix.to_vec()), Bound::Unbounded)
+    }
+}
+
+impl RangeInterval<String> {
+    /// The range of all the strings starting with the prefix, `str_prefix("user/")` is `<"user/".."user0")`.
+    /// The end is unbounded if there is no string after all the strings with the prefix.
+    pub fn str_prefix(prefix: &str) -> Self {
+        let mut end = prefix.to_string();
+        while let Some(last) = end.pop() {
+            // the next character, skipping the surrogates which are not characters
+            let next = (last as u32 + 1..=char::MAX as u32).find_map(char::from_u32);
+            if let Some(next) = next {
+                end.push(next);
+                return RangeInterval::new(Bound::Included(prefix.to_string()), Bound::Excluded(end));
+            }
+        }
+        RangeInterval::new(Bound::Included(prefix.to_string()), Bound::Unbounded)
+    }
+}
+
+/// The shortest key which is at least the first key and less than the second key, None if the first key
+/// is not less than the second key. Useful as the separator between the blocks of keys.
+pub fn shortest_separator(a: &[u8], b: &[u8]) -> Option<Vec<u8>> {
+    if a >= b {
+        return None;
+    }
+    let i = match a.iter().zip(b).position(|(x, y)| x != y) {
+        Some(i) => i,
+        // the first key is a prefix of the second one
+        None => return Some(a.to_vec()),
+    };
+    if i + 1 == a.len() {
+        return Some(a.to_vec());
+    }
+    if a[i] + 1 < b[i] {
+        let mut separator = a[..i].to_vec();
+        separator.push(a[i] + 1);
+        return Some(separator);
+    }
+    if i + 1 < b.len() {
+        return Some(b[..=i].to_vec());
+    }
+    // only the keys starting with a[..=i] are left, the first byte of the first key which can be increased
+    for j in i + 1..a.len() {
+        if j + 1 == a.len() {
+            break;
+        }
+        if a[j] < u8::MAX {
+            let mut separator = a[..j].to_vec();
+            separator.push(a[j] + 1);
+            return Some(separator);
+        }
+    }
+    Some(a.to_vec())
+}
+
+/// The separator of the strings, the shortest byte separator if it is a valid string, or the first string.
+pub fn shortest_str_separator(a: &str, b: &str) -> Option<String> {
+    let separator = shortest_separator(a.as_bytes(), b.as_bytes())?;
+    Some(String::from_utf8(separator).unwrap_or_else(|_| a.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::BTreeMap;
+    use crate::successor::Successor;
+
+    #[test]
+    fn test_successor() {
+        assert_eq!(b"ab".to_vec().next(), Some(b"ab\0".to_vec()));
+        assert_eq!(b"ab\0".to_vec().prev(), Some(b"ab".to_vec()));
+        assert_eq!(b"ab".to_vec().prev(), None);
+        assert_eq!(Vec::<u8>::new().prev(), None);
+        assert_eq!("ab".to_string().next(), Some("ab\0".to_string()));
+        assert_eq!("ab\0".to_string().prev(), Some("ab".to_string()));
+        assert_eq!("ab".to_string().prev(), None);
+        let range = RangeInterval::new(Bound::Excluded(b"a".to_vec()), Bound::Included(b"b".to_vec()));
+        let half_open = RangeInterval::new(Bound::Included(b"a\0".to_vec()), Bound::Excluded(b"b\0".to_vec()));
+        assert_eq!(range.half_open(), Some(half_open));
+    }
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(
+            RangeInterval::prefix(b"user/"),
+            RangeInterval::new(Bound::Included(b"user/".to_vec()), Bound::Excluded(b"user0".to_vec()))
+        );
+        assert_eq!(
+            RangeInterval::prefix(b"a\xff\xff"),
+            RangeInterval::new(Bound::Included(b"a\xff\xff".to_vec()), Bound::Excluded(b"b".to_vec()))
+        );
+        assert_eq!(
+            RangeInterval::prefix(b"\xff"),
+            RangeInterval::new(Bound::Included(b"\xff".to_vec()), Bound::Unbounded)
+        );
+        assert_eq!(
+            RangeInterval::str_prefix("user/"),
+            RangeInterval::new(Bound::Included("user/".to_string()), Bound::Excluded("user0".to_string()))
+        );
+        assert_eq!(
+            RangeInterval::str_prefix("a\u{d7ff}"),
+            RangeInterval::new(Bound::Included("a\u{d7ff}".to_string()), Bound::Excluded("a\u{e000}".to_string()))
+        );
+
+        let mut map = BTreeMap::new();
+        for key in ["user", "user/1", "user/2", "user0", "users"] {
+            map.insert(key.as_bytes().to_vec(), key);
+        }
+        let users: Vec<_> = map.range(RangeInterval::prefix(b"user/")).map(|(_, v)| *v).collect();
+        assert_eq!(users, vec!["user/1", "user/2"]);
+        let strings: BTreeMap<String, ()> = ["a", "ab", "b"].iter().map(|s| (s.to_string(), ())).collect();
+        assert_eq!(strings.range(RangeInterval::str_prefix("a")).count(), 2);
+    }
+
+    #[test]
+    fn test_shortest_separator() {
+        assert_eq!(shortest_separator(b"abc", b"abc"), None);
+        assert_eq!(shortest_separator(b"b", b"a"), None);
+        assert_eq!(shortest_separator(b"ab", b"abcd"), Some(b"ab".to_vec()));
+        assert_eq!(shortest_separator(b"abcd", b"abzz"), Some(b"abd".to_vec()));
+        assert_eq!(shortest_separator(b"abcd", b"abdz"), Some(b"abd".to_vec()));
+        assert_eq!(shortest_separator(b"abcd", b"abd"), Some(b"abcd".to_vec()));
+        assert_eq!(shortest_separator(b"ab\xff\xffx", b"ac"), Some(b"ab\xff\xffx".to_vec()));
+        assert_eq!(shortest_separator(b"ab\xffcx", b"ac"), Some(b"ab\xffd".to_vec()));
+        assert_eq!(shortest_separator(b"abc", b"abd"), Some(b"abc".to_vec()));
+        assert_eq!(shortest_str_separator("apple", "banana"), Some("b".to_string()));
+        assert_eq!(shortest_str_separator("a\u{e9}", "a\u{ea}"), Some("a\u{e9}".to_string()));
+        assert_eq!(shortest_str_separator("b", "a"), None);
+    }
+}
@@ -41,6 +41,85 @@ pub use binary_result::*;
 
 pub mod successor;
 
+mod bounds;
+
+#[cfg(test)]
+mod test_util;
+
+pub mod hull;
+
+pub mod arithmetic;
+pub use arithmetic::{Arithmetic, ArithmeticError};
+
+pub mod transform;
+pub use transform::Direction;
+
+pub mod normalize;
+pub use normalize::normalize;
+
+pub mod coverage;
+pub use coverage::{Coverage, CoverageEvent};
+
+pub mod allocator;
+pub use allocator::{AllocError, FitPolicy, RangeAllocator};
+
+pub mod id_allocator;
+pub use id_allocator::{IdAllocator, IdError};
+
+pub mod lock;
+pub use lock::{BlockingRangeLockTable, LockConflict, LockMode, RangeLock, RangeLockTable};
+
+pub mod sweep;
+pub use sweep::{depth_at_least, depth_profile, max_depth, merge_overlapping};
+
+pub mod scheduling;
+pub use scheduling::{assign_resources, max_non_overlapping, max_weight_schedule, min_resources};
+
+pub mod dense;
+pub use dense::{DenseIndex, DenseRangeSet};
+
+pub mod segment_tree;
+pub use segment_tree::{SegmentOps, SegmentTree};
+
+pub mod persistent;
+pub use persistent::PersistentRangeSet;
+
+pub mod calendar;
+pub use calendar::{Calendar, Recurrence};
+
+pub mod box2;
+pub use box2::{Box2, QuadResult};
+
+pub mod relation;
+pub use relation::RangeOrdering;
+
+pub mod boxn;
+pub use boxn::{BoxN, BoxSet};
+
+pub mod circular;
+pub use circular::{CircularInterval, CircularResult};
+
+pub mod keys;
+pub use keys::{shortest_separator, shortest_str_separator};
+
+pub mod edit;
+pub use edit::{apply_edit_all, EditPolicy, TextEdit};
+
+pub mod line_index;
+pub use line_index::{ColumnUnit, LineColumn, LineIndex};
+
+pub mod indexing;
+pub use indexing::{IndexError, IntervalIter, SliceRangeExt, StrRangeExt, VecRangeExt};
+
+pub mod search;
+pub use search::{count_within, count_within_by_key, slice_within, slice_within_by_key, split_within, split_within_by_key};
+
+pub mod bins;
+pub use bins::{partition_issues, Bins, PartitionIssue};
+
+pub mod interpolate;
+pub use interpolate::LerpError;
+
 
 pub mod subtraction;
 
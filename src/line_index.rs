@@ -0,0 +1,239 @@
+//! Conversion of byte offset ranges of a text to line and column ranges and back, for example for diagnostics.
+//! The lines and columns are counted from 0, and the columns can be counted in UTF-8 bytes, UTF-16 units or chars.
+
+
+use std::ops::Bound;
+use crate::range_interval::RangeInterval;
+
+/// The unit of the columns.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ColumnUnit {
+    /// The bytes of the UTF-8 encoding.
+    Utf8,
+    /// The 16 bit units of the UTF-16 encoding, as used by the language server protocol.
+    Utf16,
+    /// The characters, the Unicode scalar values.
+    Char,
+}
+
+impl ColumnUnit {
+    fn len(self, c: char) -> usize {
+        match self {
+            ColumnUnit::Utf8 => c.len_utf8(),
+            ColumnUnit::Utf16 => c.len_utf16(),
+            ColumnUnit::Char => 1,
+        }
+    }
+}
+
+/// The position in the text, ordered by the line and then by the column.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl LineColumn {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+/// Index of the starts of the lines of a text. The lines end after `'\n'`, so the `'\r'` of `"\r\n"` is
+/// the last character of the line.
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Create the index of the text.
+    pub fn new(text: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { text, starts }
+    }
+
+    /// The number of lines, the text ending with `'\n'` has an empty last line.
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// The offsets of the line, including the `'\n'` at its end.
+    fn line(&self, line: usize) -> Option<std::ops::Range<usize>> {
+        let start = *self.starts.get(line)?;
+        let end = self.starts.get(line + 1).copied().unwrap_or(self.text.len());
+        Some(start..end)
+    }
+
+    /// The position of the offset, None if the offset is past the end of the text or inside a character.
+    pub fn position(&self, offset: usize, unit: ColumnUnit) -> Option<LineColumn> {
+        if !self.text.is_char_boundary(offset) {
+            return None;
+        }
+        let line = self.starts.partition_point(|&start| start <= offset) - 1;
+        let column = self.text[self.starts[line]..offset].chars().map(|c| unit.len(c)).sum();
+        Some(LineColumn { line, column })
+    }
+
+    /// The offset of the position, None if the line does not exist, the column is past the end of the line,
+    /// or inside a character.
+    pub fn offset(&self, position: LineColumn, unit: ColumnUnit) -> Option<usize> {
+        let line = self.line(position.line)?;
+        let mut column = 0;
+        for (i, c) in self.text[line.clone()].char_indices() {
+            if column == position.column {
+                return Some(line.start + i);
+            }
+            if column > position.column {
+                return None;
+            }
+            column += unit.len(c);
+        }
+        // the end of the last line, which has no '\n'
+        if column == position.column && line.end == self.text.len() {
+            Some(line.end)
+        } else {
+            None
+        }
+    }
+
+    /// The offset after the character at the offset, None at the end of the text.
+    fn next_offset(&self, offset: usize) -> Option<usize> {
+        Some(offset + self.text.get(offset..)?.chars().next()?.len_utf8())
+    }
+
+    /// The line and column range of the offset range, half open like the offsets used by `StrRangeExt`, so the
+    /// included end byte becomes the excluded end after it and the excluded start byte becomes the included start
+    /// after it. The unbounded ends stay unbounded. None if an offset has no position.
+    pub fn to_positions(&self, range: &RangeInterval<usize>, unit: ColumnUnit) -> Option<RangeInterval<LineColumn>> {
+        let start = match range.start {
+            Bound::Included(offset) => Bound::Included(self.position(offset, unit)?),
+            Bound::Excluded(offset) => Bound::Included(self.position(offset.checked_add(1)?, unit)?),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let end = match range.end {
+            Bound::Included(offset) => Bound::Excluded(self.position(offset.checked_add(1)?, unit)?),
+            Bound::Excluded(offset) => Bound::Excluded(self.position(offset, unit)?),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        Some(RangeInterval { reverse: range.reverse, start, end })
+    }
+
+    /// The offset range of the line and column range, half open, so the included end position becomes
+    /// the excluded end after its character and the excluded start position becomes the included start after
+    /// its character. The unbounded ends stay unbounded. None if a position has no offset.
+    pub fn to_offsets(&self, range: &RangeInterval<LineColumn>, unit: ColumnUnit) -> Option<RangeInterval<usize>> {
+        let start = match range.start {
+            Bound::Included(position) => Bound::Included(self.offset(position, unit)?),
+            Bound::Excluded(position) => Bound::Included(self.next_offset(self.offset(position, unit)?)?),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let end = match range.end {
+            Bound::Included(position) => Bound::Excluded(self.next_offset(self.offset(position, unit)?)?),
+            Bound::Excluded(position) => Bound::Excluded(self.offset(position, unit)?),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        Some(RangeInterval { reverse: range.reverse, start, end })
+    }
+
+    /// The parts of the offset range on every line it spans, with the number of the line. The parts include
+    /// the `'\n'` at the end of the line, if it is in the range, and keep the kinds of the bounds of the range.
+    pub fn line_ranges(&self, range: &RangeInterval<usize>) -> impl Iterator<Item = (usize, RangeInterval<usize>)> + '_ {
+        let offsets = range.offsets(self.text.len());
+        let first = self.starts.partition_point(|&start| start <= offsets.start).max(1) - 1;
+        let range = RangeInterval { reverse: false, ..*range };
+        (first..self.line_count())
+            .take_while(move |&line| self.starts[line] < offsets.end || line == first)
+            .filter_map(move |line| {
+                let lines = self.line(line)?;
+                let whole = RangeInterval { reverse: false, start: Bound::Included(lines.start), end: Bound::Excluded(lines.end) };
+                whole.intersection(&range).map(|part| (line, part))
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    const TEXT: &str = "fn main() {\n    let é = \"😀\";\r\n}";
+
+    fn p(line: usize, column: usize) -> LineColumn {
+        LineColumn::new(line, column)
+    }
+
+    #[test]
+    fn test_position() {
+        let index = LineIndex::new(TEXT);
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.position(0, ColumnUnit::Utf8), Some(p(0, 0)));
+        assert_eq!(index.position(11, ColumnUnit::Utf8), Some(p(0, 11)));
+        assert_eq!(index.position(12, ColumnUnit::Utf8), Some(p(1, 0)));
+        // after the 'é' which has 2 bytes
+        assert_eq!(index.position(22, ColumnUnit::Utf8), Some(p(1, 10)));
+        assert_eq!(index.position(22, ColumnUnit::Char), Some(p(1, 9)));
+        assert_eq!(index.position(21, ColumnUnit::Utf8), None);
+        // after the emoji which has 4 bytes, 2 UTF-16 units and is 1 char
+        assert_eq!(index.position(30, ColumnUnit::Utf8), Some(p(1, 18)));
+        assert_eq!(index.position(30, ColumnUnit::Utf16), Some(p(1, 15)));
+        assert_eq!(index.position(30, ColumnUnit::Char), Some(p(1, 14)));
+        assert_eq!(index.position(TEXT.len(), ColumnUnit::Utf8), Some(p(2, 1)));
+        assert_eq!(index.position(TEXT.len() + 1, ColumnUnit::Utf8), None);
+    }
+
+    #[test]
+    fn test_offset() {
+        let index = LineIndex::new(TEXT);
+        for offset in (0..=TEXT.len()).filter(|&offset| TEXT.is_char_boundary(offset)) {
+            for unit in [ColumnUnit::Utf8, ColumnUnit::Utf16, ColumnUnit::Char] {
+                let position = index.position(offset, unit).unwrap();
+                assert_eq!(index.offset(position, unit), Some(offset));
+            }
+        }
+        assert_eq!(index.offset(p(1, 15), ColumnUnit::Utf16), Some(30));
+        // inside the emoji
+        assert_eq!(index.offset(p(1, 14), ColumnUnit::Utf16), None);
+        assert_eq!(index.offset(p(0, 12), ColumnUnit::Utf8), None);
+        assert_eq!(index.offset(p(3, 0), ColumnUnit::Utf8), None);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let index = LineIndex::new(TEXT);
+        let positions = index.to_positions(&r!(3..=30), ColumnUnit::Utf16).unwrap();
+        assert_eq!(positions, RangeInterval::new(Bound::Included(p(0, 3)), Bound::Excluded(p(1, 16))));
+        assert_eq!(index.to_offsets(&positions, ColumnUnit::Utf16), Some(r!(3..31)));
+        let positions = index.to_positions(&r!(30..3), ColumnUnit::Char).unwrap();
+        assert_eq!(positions, RangeInterval::new(Bound::Excluded(p(1, 15)), Bound::Included(p(0, 4))));
+        assert_eq!(index.to_offsets(&positions, ColumnUnit::Char), Some(r!(4..31).reversed()));
+        let positions = RangeInterval::new(Bound::Excluded(p(1, 8)), Bound::Included(p(1, 9)));
+        // the excluded start and included end positions are the 'é' and the following ' '
+        assert_eq!(index.to_offsets(&positions, ColumnUnit::Char), Some(r!(22..23)));
+        assert_eq!(index.to_positions(&r!(12..), ColumnUnit::Utf8), Some(RangeInterval::new(Bound::Included(p(1, 0)), Bound::Unbounded)));
+        assert_eq!(index.to_positions(&r!(3..21), ColumnUnit::Utf8), None);
+
+        // the included end is the last byte of the multibyte 'é', like in `StrRangeExt::get_interval`
+        let index = LineIndex::new("abéc");
+        assert_eq!(
+            index.to_positions(&r!(2..=3), ColumnUnit::Char),
+            Some(RangeInterval::new(Bound::Included(p(0, 2)), Bound::Excluded(p(0, 3))))
+        );
+        assert_eq!(index.to_positions(&r!(2..=2), ColumnUnit::Char), None);
+        assert_eq!(index.to_positions(&r!(!1..=3), ColumnUnit::Utf8), Some(RangeInterval::new(Bound::Included(p(0, 2)), Bound::Excluded(p(0, 4)))));
+        assert_eq!(index.to_offsets(&RangeInterval::new(Bound::Included(p(0, 2)), Bound::Included(p(0, 2))), ColumnUnit::Char), Some(r!(2..4)));
+    }
+
+    #[test]
+    fn test_line_ranges() {
+        let index = LineIndex::new(TEXT);
+        assert_eq!(index.line_ranges(&r!(3..=30)).collect::<Vec<_>>(), vec![(0, r!(3..12)), (1, r!(12..=30))]);
+        assert_eq!(index.line_ranges(&r!(!3..5)).collect::<Vec<_>>(), vec![(0, r!(!3..5))]);
+        assert_eq!(index.line_ranges(&r!(5..)).collect::<Vec<_>>(), vec![(0, r!(5..12)), (1, r!(12..34)), (2, r!(34..35))]);
+        assert_eq!(index.line_ranges(&r!(3..3)).collect::<Vec<_>>(), vec![]);
+    }
+}
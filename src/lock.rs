@@ -0,0 +1,316 @@
+//! Record locking of ranges, like `fcntl` locks on files. Shared locks of different owners can overlap, an exclusive
+//! lock conflicts with any lock of other owner. Unlocking a part of a lock splits it, and locking a range which
+//! the owner has already locked replaces the mode of that part, which upgrades or downgrades the lock.
+
+
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use crate::binary_result::BinaryResult;
+use crate::normalize::normalize;
+use crate::range_interval::RangeInterval;
+
+/// The mode of a lock.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum LockMode {
+    /// The lock can overlap with other shared locks, it is a read lock.
+    Shared,
+    /// The lock cannot overlap with any lock of other owner, it is a write lock.
+    Exclusive,
+}
+
+/// A locked range.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RangeLock<T, O> {
+    pub range: RangeInterval<T>,
+    pub owner: O,
+    pub mode: LockMode,
+}
+
+/// The lock cannot be acquired, because it conflicts with the lock of other owner.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LockConflict<T, O>(pub RangeLock<T, O>);
+
+/// Table of the locked ranges.
+#[derive(Debug, Clone)]
+pub struct RangeLockTable<T, O> {
+    locks: Vec<RangeLock<T, O>>,
+}
+
+impl<T, O> Default for RangeLockTable<T, O> {
+    fn default() -> Self {
+        Self { locks: Vec::new() }
+    }
+}
+
+impl<T: PartialOrd + Clone, O: PartialEq + Clone> RangeLockTable<T, O> {
+    /// Create the table with no locks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All the locks in the table.
+    pub fn locks(&self) -> &[RangeLock<T, O>] {
+        &self.locks
+    }
+
+    /// Return the first lock of other owner which conflicts with the requested lock.
+    pub fn conflict(&self, owner: &O, range: &RangeInterval<T>, mode: LockMode) -> Option<&RangeLock<T, O>> {
+        self.locks.iter().find(|lock| {
+            lock.owner != *owner
+                && (mode == LockMode::Exclusive || lock.mode == LockMode::Exclusive)
+                && lock.range.overlaps(range)
+        })
+    }
+
+    /// Lock the range, or change the mode of the range if the owner has already locked it.
+    /// Return the conflicting lock of other owner if the range cannot be locked.
+    pub fn try_lock(&mut self, owner: O, range: RangeInterval<T>, mode: LockMode) -> Result<(), LockConflict<T, O>> {
+        if range.is_void() {
+            return Ok(());
+        }
+        if let Some(lock) = self.conflict(&owner, &range, mode) {
+            return Err(LockConflict(lock.clone()));
+        }
+        self.unlock(&owner, range.clone());
+        // merge the lock with the locks of the same owner and mode it overlaps or touches
+        let (same, other): (Vec<_>, Vec<_>) = self.locks.drain(..)
+            .partition(|lock| lock.owner == owner && lock.mode == mode);
+        self.locks = other;
+        let ranges = normalize(same.into_iter().map(|lock| lock.range).chain(Some(range)));
+        self.locks.extend(ranges.into_iter().map(|range| RangeLock { range, owner: owner.clone(), mode }));
+        Ok(())
+    }
+
+    /// Change the mode of the owner's range to exclusive.
+    pub fn upgrade(&mut self, owner: O, range: RangeInterval<T>) -> Result<(), LockConflict<T, O>> {
+        self.try_lock(owner, range, LockMode::Exclusive)
+    }
+
+    /// Change the mode of the owner's range to shared, this never conflicts.
+    pub fn downgrade(&mut self, owner: O, range: RangeInterval<T>) -> Result<(), LockConflict<T, O>> {
+        self.try_lock(owner, range, LockMode::Shared)
+    }
+
+    /// Unlock the range of the owner's locks, the locks which are unlocked only partially are split.
+    pub fn unlock(&mut self, owner: &O, range: RangeInterval<T>) {
+        let locks = std::mem::take(&mut self.locks);
+        for lock in locks {
+            if lock.owner != *owner || !lock.range.overlaps(&range) {
+                self.locks.push(lock);
+                continue;
+            }
+            let RangeLock { range: locked, owner, mode } = lock;
+            match locked.subtract(range.clone()) {
+                BinaryResult::None => {}
+                BinaryResult::One(a) => self.locks.push(RangeLock { range: a, owner, mode }),
+                BinaryResult::Two(a, b) => {
+                    self.locks.push(RangeLock { range: a, owner: owner.clone(), mode });
+                    self.locks.push(RangeLock { range: b, owner, mode });
+                }
+            }
+        }
+    }
+
+    /// Unlock all the owner's locks.
+    pub fn unlock_all(&mut self, owner: &O) {
+        self.locks.retain(|lock| lock.owner != *owner);
+    }
+}
+
+/// Lock table for multiple threads, where acquiring a lock can wait until the conflicting locks are unlocked.
+#[derive(Debug)]
+pub struct BlockingRangeLockTable<T, O> {
+    table: Mutex<RangeLockTable<T, O>>,
+    unlocked: Condvar,
+}
+
+impl<T, O> Default for BlockingRangeLockTable<T, O> {
+    fn default() -> Self {
+        Self { table: Mutex::new(RangeLockTable::default()), unlocked: Condvar::new() }
+    }
+}
+
+impl<T: PartialOrd + Clone, O: PartialEq + Clone> BlockingRangeLockTable<T, O> {
+    /// Create the table with no locks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn table(&self) -> MutexGuard<'_, RangeLockTable<T, O>> {
+        self.table.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// All the locks in the table.
+    pub fn locks(&self) -> Vec<RangeLock<T, O>> {
+        self.table().locks().to_vec()
+    }
+
+    /// Lock the range in the locked table, and wake up the waiting threads if it downgrades an exclusive lock,
+    /// which can allow other shared locks.
+    fn acquire(&self, table: &mut RangeLockTable<T, O>, owner: &O, range: &RangeInterval<T>, mode: LockMode) -> Result<(), LockConflict<T, O>> {
+        let downgrade = mode == LockMode::Shared && table.locks().iter()
+            .any(|lock| lock.owner == *owner && lock.mode == LockMode::Exclusive && lock.range.overlaps(range));
+        table.try_lock(owner.clone(), range.clone(), mode)?;
+        if downgrade {
+            self.unlocked.notify_all();
+        }
+        Ok(())
+    }
+
+    /// Lock the range without waiting, return the conflicting lock if the range cannot be locked.
+    pub fn try_lock(&self, owner: O, range: RangeInterval<T>, mode: LockMode) -> Result<(), LockConflict<T, O>> {
+        self.acquire(&mut self.table(), &owner, &range, mode)
+    }
+
+    /// Lock the range, waiting until there is no conflicting lock.
+    pub fn lock(&self, owner: O, range: RangeInterval<T>, mode: LockMode) {
+        let mut table = self.table();
+        while self.acquire(&mut table, &owner, &range, mode).is_err() {
+            table = self.unlocked.wait(table).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Lock the range, waiting at most the timeout, return the conflicting lock if the range cannot be locked.
+    pub fn lock_timeout(&self, owner: O, range: RangeInterval<T>, mode: LockMode, timeout: Duration) -> Result<(), LockConflict<T, O>> {
+        let deadline = Instant::now() + timeout;
+        let mut table = self.table();
+        loop {
+            let conflict = match self.acquire(&mut table, &owner, &range, mode) {
+                Ok(()) => return Ok(()),
+                Err(conflict) => conflict,
+            };
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(conflict);
+            }
+            table = self.unlocked.wait_timeout(table, deadline - now).unwrap_or_else(PoisonError::into_inner).0;
+        }
+    }
+
+    /// Unlock the range of the owner's locks, and wake up the waiting threads.
+    pub fn unlock(&self, owner: &O, range: RangeInterval<T>) {
+        self.table().unlock(owner, range);
+        self.unlocked.notify_all();
+    }
+
+    /// Unlock all the owner's locks, and wake up the waiting threads.
+    pub fn unlock_all(&self, owner: &O) {
+        self.table().unlock_all(owner);
+        self.unlocked.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::sync::mpsc;
+    use std::thread;
+    use super::*;
+
+    use crate::r;
+
+    fn locks(table: &RangeLockTable<u64, u32>, owner: u32) -> Vec<(RangeInterval<u64>, LockMode)> {
+        let mut locks: Vec<_> = table.locks().iter()
+            .filter(|lock| lock.owner == owner)
+            .map(|lock| (lock.range, lock.mode))
+            .collect();
+        locks.sort_by(|a, b| a.0.cmp_bounds(&b.0));
+        locks
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let mut table = RangeLockTable::new();
+        assert_eq!(table.try_lock(1, r!(0..10), LockMode::Shared), Ok(()));
+        assert_eq!(table.try_lock(2, r!(5..15), LockMode::Shared), Ok(()));
+        assert_eq!(
+            table.try_lock(3, r!(8..9), LockMode::Exclusive),
+            Err(LockConflict(RangeLock { range: r!(0..10), owner: 1, mode: LockMode::Shared }))
+        );
+        assert_eq!(table.try_lock(3, r!(15..20), LockMode::Exclusive), Ok(()));
+        assert_eq!(
+            table.try_lock(1, r!(!14..16), LockMode::Shared),
+            Err(LockConflict(RangeLock { range: r!(15..20), owner: 3, mode: LockMode::Exclusive }))
+        );
+        assert_eq!(table.try_lock(1, r!(!14..15), LockMode::Shared), Ok(()));
+        assert!(table.conflict(&4, &r!(20..), LockMode::Exclusive).is_none());
+        assert!(table.conflict(&4, &r!(19..), LockMode::Shared).is_some());
+    }
+
+    #[test]
+    fn test_split_and_merge() {
+        let mut table = RangeLockTable::new();
+        assert_eq!(table.try_lock(1, r!(0..100), LockMode::Exclusive), Ok(()));
+        table.unlock(&1, r!(40..60));
+        assert_eq!(locks(&table, 1), vec![(r!(0..40), LockMode::Exclusive), (r!(60..100), LockMode::Exclusive)]);
+        assert_eq!(table.try_lock(2, r!(45..55), LockMode::Exclusive), Ok(()));
+        assert_eq!(table.try_lock(1, r!(40..45), LockMode::Exclusive), Ok(()));
+        assert_eq!(locks(&table, 1), vec![(r!(0..45), LockMode::Exclusive), (r!(60..100), LockMode::Exclusive)]);
+        table.unlock(&1, r!(..=10));
+        assert_eq!(locks(&table, 1), vec![(r!(!10..45), LockMode::Exclusive), (r!(60..100), LockMode::Exclusive)]);
+        table.unlock_all(&1);
+        assert_eq!(locks(&table, 1), vec![]);
+        assert_eq!(locks(&table, 2), vec![(r!(45..55), LockMode::Exclusive)]);
+    }
+
+    #[test]
+    fn test_upgrade_downgrade() {
+        let mut table = RangeLockTable::new();
+        assert_eq!(table.try_lock(1, r!(0..100), LockMode::Shared), Ok(()));
+        assert_eq!(table.upgrade(1, r!(20..30)), Ok(()));
+        assert_eq!(locks(&table, 1), vec![
+            (r!(0..20), LockMode::Shared),
+            (r!(20..30), LockMode::Exclusive),
+            (r!(30..100), LockMode::Shared),
+        ]);
+        assert_eq!(table.try_lock(2, r!(50..60), LockMode::Shared), Ok(()));
+        assert!(table.upgrade(1, r!(40..70)).is_err());
+        assert_eq!(table.downgrade(1, r!(..)), Ok(()));
+        assert_eq!(locks(&table, 1), vec![(r!(..), LockMode::Shared)]);
+        assert_eq!(table.try_lock(2, r!(20..30), LockMode::Shared), Ok(()));
+        assert_eq!(locks(&table, 2), vec![(r!(20..30), LockMode::Shared), (r!(50..60), LockMode::Shared)]);
+    }
+
+    #[test]
+    fn test_blocking() {
+        let table = Arc::new(BlockingRangeLockTable::new());
+        table.lock(1, r!(0u64..10), LockMode::Exclusive);
+        assert!(table.lock_timeout(2, r!(5..15), LockMode::Shared, Duration::from_millis(10)).is_err());
+
+        let (sender, receiver) = mpsc::channel();
+        let waiting = {
+            let table = table.clone();
+            thread::spawn(move || {
+                table.lock(2, r!(5..15), LockMode::Shared);
+                sender.send(()).unwrap();
+            })
+        };
+        assert!(receiver.recv_timeout(Duration::from_millis(20)).is_err());
+        table.unlock(&1, r!(0..10));
+        receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        waiting.join().unwrap();
+        assert_eq!(table.locks(), vec![RangeLock { range: r!(5..15), owner: 2, mode: LockMode::Shared }]);
+        assert!(table.try_lock(1, r!(0..6), LockMode::Exclusive).is_err());
+        assert_eq!(table.lock_timeout(1, r!(0..5), LockMode::Exclusive, Duration::from_millis(10)), Ok(()));
+    }
+
+    #[test]
+    fn test_blocking_downgrade() {
+        let table = Arc::new(BlockingRangeLockTable::new());
+        table.lock(1, r!(0u64..10), LockMode::Exclusive);
+
+        let (sender, receiver) = mpsc::channel();
+        let waiting = {
+            let table = table.clone();
+            thread::spawn(move || {
+                table.lock(2, r!(0..10), LockMode::Shared);
+                sender.send(()).unwrap();
+            })
+        };
+        assert!(receiver.recv_timeout(Duration::from_millis(20)).is_err());
+        table.lock(1, r!(0..10), LockMode::Shared);
+        receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        waiting.join().unwrap();
+        assert_eq!(table.locks().len(), 2);
+    }
+}
@@ -0,0 +1,96 @@
+//! Normalization of multiple ranges into sorted disjoint ranges.
+
+
+use crate::bounds::max_cut;
+use crate::range_interval::RangeInterval;
+
+/// Create normalized `Vec<RangeInterval>` from a list of ranges, each written in the syntax of the `r` macro.
+/// ```
+/// use range_ext::{r, rs};
+///
+/// let a = rs![10.., 1..3, !5..=8, 2..4];
+/// assert_eq!(a, vec![r!(1..4), r!(!5..=8), r!(10..)]);
+/// ```
+#[macro_export]
+macro_rules! rs {
+        (@split [$($done: tt)*] [$($range: tt)+] , $($rest: tt)*) => {
+            $crate::rs!(@split [$($done)* $crate::r!($($range)+),] [] $($rest)*)
+        };
+        (@split [$($done: tt)*] [$($range: tt)*] $t: tt $($rest: tt)*) => {
+            $crate::rs!(@split [$($done)*] [$($range)* $t] $($rest)*)
+        };
+        (@split [$($done: tt)*] [$($range: tt)+]) => {
+            $crate::normalize::normalize(::std::vec![$($done)* $crate::r!($($range)+)])
+        };
+        (@split [$($done: tt)*] []) => {
+            $crate::normalize::normalize(::std::vec![$($done)*])
+        };
+        ($($t: tt)*) => {
+            $crate::rs!(@split [] [] $($t)*)
+        };
+    }
+
+/// Sort the ranges and merge those which overlap or touch each other, so the result are disjoint ranges
+/// covering the same values. Empty ranges are removed and all the ranges are returned in forward direction.
+pub fn normalize<T: PartialOrd + Clone>(ranges: impl IntoIterator<Item = RangeInterval<T>>) -> Vec<RangeInterval<T>> {
+    let mut ranges: Vec<_> = ranges.into_iter()
+        .filter(|range| !range.is_void())
+        .map(|range| RangeInterval { reverse: false, ..range })
+        .collect();
+    ranges.sort_by(|a, b| a.cmp_bounds(b));
+    let mut result: Vec<RangeInterval<T>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match result.last_mut() {
+            Some(last) if last.connects(&range) => {
+                last.end = max_cut(last.end_cut(), range.end_cut()).to_end();
+            }
+            _ => result.push(range),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+    use crate::test_util::D;
+
+    fn display(ranges: Vec<RangeInterval<i32>>) -> String {
+        ranges.into_iter().map(|range| range.display::<D>().to_string()).collect::<Vec<_>>().join(", ")
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(vec![r!(1..3), r!(2..5)]), vec![r!(1..5)]);
+        assert_eq!(normalize(vec![r!(2..5), r!(1..3)]), vec![r!(1..5)]);
+        assert_eq!(normalize(vec![r!(1..3), r!(3..5)]), vec![r!(1..5)]);
+        assert_eq!(normalize(vec![r!(1..=3), r!(!3..5)]), vec![r!(1..5)]);
+        assert_eq!(normalize(vec![r!(1..3), r!(!3..5)]), vec![r!(1..3), r!(!3..5)]);
+        assert_eq!(normalize(vec![r!(1..10), r!(2..3)]), vec![r!(1..10)]);
+        assert_eq!(normalize(vec![r!(1..10), r!(!1..=10)]), vec![r!(1..=10)]);
+        assert_eq!(normalize(vec![r!(5..1), r!(7..=9)]), vec![r!(!1..=5), r!(7..=9)]);
+        assert_eq!(normalize(vec![r!(1..1), r!(7..=9)]), vec![r!(7..=9)]);
+        assert_eq!(normalize(vec![r!(..1), r!(7..=9), r!(0..)]), vec![r!(..)]);
+        assert_eq!(normalize(Vec::<RangeInterval<i32>>::new()), vec![]);
+    }
+
+    #[test]
+    fn test_macro() {
+        assert_eq!(rs![1..3, !5..=8, 10..], vec![r!(1..3), r!(!5..=8), r!(10..)]);
+        assert_eq!(rs![10.., !5..=8, 1..3,], vec![r!(1..3), r!(!5..=8), r!(10..)]);
+        assert_eq!(rs![1..3, 2..=8, 8..], vec![r!(1..)]);
+        assert_eq!(rs![5], vec![r!(5..=5)]);
+        let a = 4;
+        assert_eq!(rs![..a, a * 2..=a * 3], vec![r!(..4), r!(8..=12)]);
+        assert_eq!(rs![rev: 1..3], vec![r!(1..3)]);
+        assert_eq!(rs![], Vec::<RangeInterval<i32>>::new());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(display(rs![10.., 1..3, !5..=8, 2..4]), "<1..4), (5..8>, <10..");
+        assert_eq!(display(rs![!5..=8, 2..4, 10.., 1..3]), "<1..4), (5..8>, <10..");
+    }
+}
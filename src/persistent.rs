@@ -0,0 +1,405 @@
+//! Persistent ordered collection of ranges. Inserting or removing a range returns a new version of the collection
+//! and keeps the old one, the versions share all the nodes except the O(log n) nodes on the changed path,
+//! so keeping many versions is cheap.
+
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::FromIterator;
+use std::sync::Arc;
+use crate::range_interval::RangeInterval;
+
+type Link<T> = Option<Arc<Node<T>>>;
+
+struct Node<T> {
+    range: RangeInterval<T>,
+    left: Link<T>,
+    right: Link<T>,
+    height: usize,
+    len: usize,
+}
+
+fn height<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn size<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.len)
+}
+
+fn node<T>(range: RangeInterval<T>, left: Link<T>, right: Link<T>) -> Arc<Node<T>> {
+    let height = 1 + height(&left).max(height(&right));
+    let len = 1 + size(&left) + size(&right);
+    Arc::new(Node { range, left, right, height, len })
+}
+
+/// The order of the ranges, by the start, then by the end, and the forward range before the reversed one.
+fn compare<T: PartialOrd>(a: &RangeInterval<T>, b: &RangeInterval<T>) -> Ordering {
+    a.cmp_bounds(b).then_with(|| a.reverse.cmp(&b.reverse))
+}
+
+/// Create the node and rotate it, if the heights of the children differ by more than one.
+fn balance<T: Clone>(range: RangeInterval<T>, left: Link<T>, right: Link<T>) -> Arc<Node<T>> {
+    let (hl, hr) = (height(&left), height(&right));
+    if hl > hr + 1 {
+        let l = left.as_ref().expect("higher child exists");
+        if height(&l.left) >= height(&l.right) {
+            node(l.range.clone(), l.left.clone(), Some(node(range, l.right.clone(), right)))
+        } else {
+            let lr = l.right.as_ref().expect("higher child exists");
+            node(
+                lr.range.clone(),
+                Some(node(l.range.clone(), l.left.clone(), lr.left.clone())),
+                Some(node(range, lr.right.clone(), right)),
+            )
+        }
+    } else if hr > hl + 1 {
+        let r = right.as_ref().expect("higher child exists");
+        if height(&r.right) >= height(&r.left) {
+            node(r.range.clone(), Some(node(range, left, r.left.clone())), r.right.clone())
+        } else {
+            let rl = r.left.as_ref().expect("higher child exists");
+            node(
+                rl.range.clone(),
+                Some(node(range, left, rl.left.clone())),
+                Some(node(r.range.clone(), rl.right.clone(), r.right.clone())),
+            )
+        }
+    } else {
+        node(range, left, right)
+    }
+}
+
+fn insert<T: PartialOrd + Clone>(link: &Link<T>, range: RangeInterval<T>) -> Arc<Node<T>> {
+    match link {
+        None => node(range, None, None),
+        Some(n) => match compare(&range, &n.range) {
+            Ordering::Less => balance(n.range.clone(), Some(insert(&n.left, range)), n.right.clone()),
+            Ordering::Greater => balance(n.range.clone(), n.left.clone(), Some(insert(&n.right, range))),
+            Ordering::Equal => node(range, n.left.clone(), n.right.clone()),
+        }
+    }
+}
+
+/// Remove the first range of the subtree, return the range and the rest of the subtree.
+fn remove_first<T: PartialOrd + Clone>(n: &Node<T>) -> (RangeInterval<T>, Link<T>) {
+    match &n.left {
+        None => (n.range.clone(), n.right.clone()),
+        Some(left) => {
+            let (first, left) = remove_first(left);
+            (first, Some(balance(n.range.clone(), left, n.right.clone())))
+        }
+    }
+}
+
+/// Remove the range from the subtree, None if the range is not there.
+fn remove<T: PartialOrd + Clone>(link: &Link<T>, range: &RangeInterval<T>) -> Option<Link<T>> {
+    let n = link.as_ref()?;
+    Some(match compare(range, &n.range) {
+        Ordering::Less => Some(balance(n.range.clone(), remove(&n.left, range)?, n.right.clone())),
+        Ordering::Greater => Some(balance(n.range.clone(), n.left.clone(), remove(&n.right, range)?)),
+        Ordering::Equal => match (&n.left, &n.right) {
+            (None, right) => right.clone(),
+            (left, None) => left.clone(),
+            (left, Some(right)) => {
+                let (first, right) = remove_first(right);
+                Some(balance(first, left.clone(), right))
+            }
+        },
+    })
+}
+
+/// The ranges of a version not walked by `diff` yet, the whole subtrees are expanded only if they are not shared
+/// with the other version.
+enum Pending<'a, T> {
+    Tree(&'a Arc<Node<T>>),
+    Range(&'a RangeInterval<T>),
+}
+
+impl<T> Clone for Pending<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Pending<'_, T> {}
+
+fn push_tree<'a, T>(stack: &mut Vec<Pending<'a, T>>, link: &'a Link<T>) {
+    if let Some(n) = link {
+        stack.push(Pending::Tree(n));
+    }
+}
+
+/// Replace the subtree on the top of the stack by its left subtree, its range and its right subtree.
+fn expand<T>(stack: &mut Vec<Pending<'_, T>>) {
+    if let Some(&Pending::Tree(n)) = stack.last() {
+        stack.pop();
+        push_tree(stack, &n.right);
+        stack.push(Pending::Range(&n.range));
+        push_tree(stack, &n.left);
+    }
+}
+
+/// A difference between two versions of the collection.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Change<'a, T> {
+    /// The range is only in the newer version.
+    Added(&'a RangeInterval<T>),
+    /// The range is only in the older version.
+    Removed(&'a RangeInterval<T>),
+}
+
+/// Persistent ordered collection of ranges, sorted by the start, then by the end. Cloning is O(1).
+pub struct PersistentRangeSet<T> {
+    root: Link<T>,
+}
+
+impl<T> Clone for PersistentRangeSet<T> {
+    fn clone(&self) -> Self {
+        Self { root: self.root.clone() }
+    }
+}
+
+impl<T> Default for PersistentRangeSet<T> {
+    fn default() -> Self {
+        Self { root: None }
+    }
+}
+
+impl<T> PersistentRangeSet<T> {
+    /// Create an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of ranges.
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    /// Test if there is no range.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Iterate the ranges in the order of the bounds.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    /// Test if both versions share the same nodes, so they are equal without comparing the ranges.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> PersistentRangeSet<T> {
+    /// Test if the range is in the collection.
+    pub fn contains(&self, range: &RangeInterval<T>) -> bool {
+        let mut link = &self.root;
+        while let Some(n) = link {
+            link = match compare(range, &n.range) {
+                Ordering::Less => &n.left,
+                Ordering::Greater => &n.right,
+                Ordering::Equal => return true,
+            };
+        }
+        false
+    }
+
+    /// The new version with the range, in O(log n). This version is not changed.
+    pub fn insert(&self, range: RangeInterval<T>) -> Self {
+        Self { root: Some(insert(&self.root, range)) }
+    }
+
+    /// The new version without the range, in O(log n). This version is not changed.
+    pub fn remove(&self, range: &RangeInterval<T>) -> Self {
+        match remove(&self.root, range) {
+            Some(root) => Self { root },
+            None => self.clone(),
+        }
+    }
+
+    /// The changes from the older version to this version, in the order of the bounds. The subtrees shared by
+    /// both versions are skipped, so the diff of the close versions walks only the changed paths.
+    pub fn diff<'a>(&'a self, older: &'a Self) -> Vec<Change<'a, T>> {
+        let mut changes = Vec::new();
+        let (mut new, mut old) = (Vec::new(), Vec::new());
+        push_tree(&mut new, &self.root);
+        push_tree(&mut old, &older.root);
+        loop {
+            match (new.last().copied(), old.last().copied()) {
+                (Some(Pending::Tree(a)), Some(Pending::Tree(b))) if Arc::ptr_eq(a, b) => {
+                    new.pop();
+                    old.pop();
+                }
+                (Some(Pending::Tree(a)), Some(Pending::Tree(b))) => {
+                    // the higher subtree is expanded first, the shared subtrees are at the same height
+                    if a.height >= b.height {
+                        expand(&mut new);
+                    }
+                    if b.height >= a.height {
+                        expand(&mut old);
+                    }
+                }
+                (Some(Pending::Tree(_)), _) => expand(&mut new),
+                (_, Some(Pending::Tree(_))) => expand(&mut old),
+                (Some(Pending::Range(a)), Some(Pending::Range(b))) => match compare(a, b) {
+                    Ordering::Less => {
+                        changes.push(Change::Added(a));
+                        new.pop();
+                    }
+                    Ordering::Greater => {
+                        changes.push(Change::Removed(b));
+                        old.pop();
+                    }
+                    Ordering::Equal => {
+                        new.pop();
+                        old.pop();
+                    }
+                },
+                (Some(Pending::Range(a)), None) => {
+                    changes.push(Change::Added(a));
+                    new.pop();
+                }
+                (None, Some(Pending::Range(b))) => {
+                    changes.push(Change::Removed(b));
+                    old.pop();
+                }
+                (None, None) => break,
+            }
+        }
+        changes
+    }
+}
+
+impl<T: PartialOrd + Clone> FromIterator<RangeInterval<T>> for PersistentRangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInterval<T>>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), |set, range| set.insert(range))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentRangeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Iterator of the ranges of the collection in the order of the bounds.
+pub struct Iter<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut link: &'a Link<T>) {
+        while let Some(n) = link {
+            self.stack.push(n);
+            link = &n.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a RangeInterval<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.stack.pop()?;
+        self.push_left(&n.right);
+        Some(&n.range)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PersistentRangeSet<T> {
+    type Item = &'a RangeInterval<T>;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_versions() {
+        let v0 = PersistentRangeSet::new();
+        let v1 = v0.insert(r!(5..10)).insert(r!(1..3)).insert(r!(1..=3));
+        let v2 = v1.insert(r!(10..5)).remove(&r!(1..3));
+        assert!(v0.is_empty());
+        assert_eq!(v1.len(), 3);
+        assert_eq!(v1.iter().cloned().collect::<Vec<_>>(), vec![r!(1..3), r!(1..=3), r!(5..10)]);
+        assert_eq!(v2.iter().cloned().collect::<Vec<_>>(), vec![r!(1..=3), r!(5..10), r!(10..5)]);
+        assert!(v2.contains(&r!(10..5)));
+        assert!(!v2.contains(&r!(1..3)));
+        assert!(v1.contains(&r!(1..3)));
+        assert!(v1.remove(&r!(7..8)).ptr_eq(&v1));
+        assert!(!v1.insert(r!(5..10)).ptr_eq(&v1));
+        assert_eq!(v1.insert(r!(5..10)).len(), 3);
+    }
+
+    #[test]
+    fn test_diff() {
+        let v1: PersistentRangeSet<i32> = (0..10).map(|i| r!(i..i + 1)).collect();
+        let v2 = v1.remove(&r!(3..4)).insert(r!(3..=4)).insert(r!(20..));
+        assert_eq!(v2.diff(&v1), vec![Change::Removed(&r!(3..4)), Change::Added(&r!(3..=4)), Change::Added(&r!(20..))]);
+        assert_eq!(v1.diff(&v2), vec![Change::Added(&r!(3..4)), Change::Removed(&r!(3..=4)), Change::Removed(&r!(20..))]);
+        assert_eq!(v2.diff(&v2.clone()), vec![]);
+        assert_eq!(v1.diff(&v1.insert(r!(0..1))), vec![]);
+        assert_eq!(v1.diff(&PersistentRangeSet::new()).len(), 10);
+        assert_eq!(PersistentRangeSet::new().diff(&v1).len(), 10);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Counted(i32);
+
+    static COMPARISONS: AtomicUsize = AtomicUsize::new(0);
+
+    impl PartialOrd for Counted {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            COMPARISONS.fetch_add(1, AtomicOrdering::Relaxed);
+            self.0.partial_cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn test_diff_shared() {
+        let v1: PersistentRangeSet<Counted> = (0..1000).map(|i| r!(Counted(i)..Counted(i + 1))).collect();
+        let v2 = v1.remove(&r!(Counted(500)..Counted(501))).insert(r!(Counted(2000)..));
+        COMPARISONS.store(0, AtomicOrdering::Relaxed);
+        assert_eq!(
+            v2.diff(&v1),
+            vec![Change::Removed(&r!(Counted(500)..Counted(501))), Change::Added(&r!(Counted(2000)..))]
+        );
+        assert!(COMPARISONS.load(AtomicOrdering::Relaxed) < 100);
+    }
+
+    #[test]
+    fn test_balance() {
+        let mut set = PersistentRangeSet::new();
+        let mut versions = Vec::new();
+        for i in 0..1000 {
+            set = set.insert(r!(i..i + 10));
+            versions.push(set.clone());
+        }
+        assert!(height(&set.root) <= 15);
+        for i in (0..1000).step_by(2) {
+            set = set.remove(&r!(i..i + 10));
+        }
+        assert!(height(&set.root) <= 15);
+        assert_eq!(set.len(), 500);
+        assert_eq!(set.iter().next(), Some(&r!(1..11)));
+        assert_eq!(versions[9].len(), 10);
+        assert_eq!(versions[9].iter().last(), Some(&r!(9..19)));
+    }
+}
@@ -1,40 +1,67 @@
 ///! Unified structure for representing ranges and intervals.
 
 use std::fmt::{Display, Formatter};
-use std::ops::{Bound, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+use std::ops::{Bound, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
 use crate::successor::Successor;
 
+/// Create `RangeInterval` with the range syntax, the ends can be any expressions.
+/// An exclamation mark '!' before the start excludes the start, an exclamation mark before the end is optional
+/// and only emphasizes that the end is excluded. A single value is a range containing only that value.
+/// The marker `rev:` before the range returns the range in the opposite direction.
+/// ```
+/// use range_ext::r;
+///
+/// let a = 3;
+/// let b = r!(!a..=a * 2); // (3..6>
+/// let c = r!(-5..!a); // <-5..3)
+/// let d = r!(rev: 1..10); // (10..1>
+/// assert!(b.contains(6) && !b.contains(3));
+/// assert!(c.contains(-5) && !c.contains(3));
+/// assert!(d.reverse);
+/// ```
 #[macro_export]
 macro_rules! r {
-        (..) => {
-            RangeInterval::new(Bound::Unbounded, Bound::Unbounded)
+        (@start $s: ident [$($a: tt)+] ..= $($b: tt)+) => {
+            $crate::range_interval::RangeInterval::new(::std::ops::Bound::$s($($a)+), ::std::ops::Bound::Included($($b)+))
+        };
+        (@start $s: ident [$($a: tt)+] .. ! $($b: tt)+) => {
+            $crate::range_interval::RangeInterval::new(::std::ops::Bound::$s($($a)+), ::std::ops::Bound::Excluded($($b)+))
         };
-        (.. $b: literal) => {
-            RangeInterval::new(Bound::Unbounded, Bound::Excluded($b))
+        (@start $s: ident [$($a: tt)+] .. $($b: tt)+) => {
+            $crate::range_interval::RangeInterval::new(::std::ops::Bound::$s($($a)+), ::std::ops::Bound::Excluded($($b)+))
         };
-        (..= $b: literal) => {
-            RangeInterval::new(Bound::Unbounded, Bound::Included($b))
+        (@start $s: ident [$($a: tt)+] ..) => {
+            $crate::range_interval::RangeInterval::new(::std::ops::Bound::$s($($a)+), ::std::ops::Bound::Unbounded)
         };
-        ($a: literal .. $b: literal) => {
-            RangeInterval::new(Bound::Included($a), Bound::Excluded($b))
+        (@start Included [$($a: tt)+]) => {
+            match $($a)+ {
+                a => $crate::range_interval::RangeInterval::new(::std::ops::Bound::Included(a.clone()), ::std::ops::Bound::Included(a)),
+            }
         };
-        ($a: literal ..= $b: literal) => {
-            RangeInterval::new(Bound::Included($a), Bound::Included($b))
+        (@start $s: ident [$($a: tt)*] $t: tt $($rest: tt)*) => {
+            $crate::r!(@start $s [$($a)* $t] $($rest)*)
+        };
+        (..) => {
+            $crate::range_interval::RangeInterval::new(::std::ops::Bound::Unbounded, ::std::ops::Bound::Unbounded)
         };
-        ($a: literal ..) => {
-            RangeInterval::new(Bound::Included($a), Bound::Unbounded)
+        (..= $($b: tt)+) => {
+            $crate::range_interval::RangeInterval::new(::std::ops::Bound::Unbounded, ::std::ops::Bound::Included($($b)+))
         };
-        ($a: literal) => {
-            RangeInterval::new(Bound::Included($a), Bound::Included($a))
+        (.. ! $($b: tt)+) => {
+            $crate::range_interval::RangeInterval::new(::std::ops::Bound::Unbounded, ::std::ops::Bound::Excluded($($b)+))
         };
-        (!$a: literal ..) => {
-            RangeInterval::new(Bound::Excluded($a), Bound::Unbounded)
+        (.. $($b: tt)+) => {
+            $crate::range_interval::RangeInterval::new(::std::ops::Bound::Unbounded, ::std::ops::Bound::Excluded($($b)+))
         };
-        (!$a: literal .. $b: literal) => {
-            RangeInterval::new(Bound::Excluded($a), Bound::Excluded($b))
+        // the colon cannot follow an identifier at the start of an expression, so a variable named rev still works
+        (rev : $($t: tt)+) => {
+            $crate::r!($($t)+).reversed()
         };
-        (!$a: literal ..= $b: literal) => {
-            RangeInterval::new(Bound::Excluded($a), Bound::Included($b))
+        (! $($t: tt)+) => {
+            $crate::r!(@start Excluded [] $($t)+)
+        };
+        ($($t: tt)+) => {
+            $crate::r!(@start Included [] $($t)+)
         };
     }
 /// A range interval is a range with a start and end bound.
@@ -72,6 +99,18 @@ impl<T: DisplayExt> Display for RangeInterval<T> {
     }
 }
 
+/// The range can be used directly as the argument of `BTreeMap::range` or `BTreeSet::range`, the direction
+/// is ignored. Those panic for the range with start greater than end, or with both ends excluded at the same value.
+impl<T> RangeBounds<T> for RangeInterval<T> {
+    fn start_bound(&self) -> Bound<&T> {
+        self.start.as_ref()
+    }
+
+    fn end_bound(&self) -> Bound<&T> {
+        self.end.as_ref()
+    }
+}
+
 fn into_bound<T, F: From<T>>(bound: Bound<T>) -> Bound<F> {
     match bound {
         Bound::Included(v) => Bound::Included(F::from(v)),
@@ -267,6 +306,39 @@ impl<T: PartialOrd + Successor + Clone> RangeInterval<T> {
     pub fn avoid(&self, value: T, direction_end: bool ) -> Option<T> {
         self.avoid_with(value, direction_end, |v, dir| if dir { v.next() } else { v.prev() })
     }
+
+    /// Convert excluded bounds to included bounds of the next or previous value, so `(1..5)` becomes `<2..4>`.
+    /// Return None if there is no value in the range.
+    pub fn closed(self) -> Option<Self> {
+        let start = match self.start {
+            Bound::Excluded(a) => Bound::Included(a.next()?),
+            start => start,
+        };
+        let end = match self.end {
+            Bound::Excluded(b) => Bound::Included(b.prev()?),
+            end => end,
+        };
+        if let (Bound::Included(a), Bound::Included(b)) = (&start, &end) {
+            if a > b {
+                return None;
+            }
+        }
+        Some(RangeInterval { reverse: self.reverse, start, end })
+    }
+
+    /// Convert to the range with included start and excluded end, so `(1..5>` becomes `<2..6)`.
+    /// The end which is the maximal value stays included. Return None if there is no value in the range.
+    pub fn half_open(self) -> Option<Self> {
+        let closed = self.closed()?;
+        let end = match closed.end {
+            Bound::Included(b) => match b.next() {
+                Some(next) => Bound::Excluded(next),
+                None => Bound::Included(b),
+            },
+            end => end,
+        };
+        Some(RangeInterval { end, ..closed })
+    }
 }
 
 impl<T: PartialOrd> RangeInterval<T> {
@@ -290,6 +362,15 @@ impl<T: PartialOrd> RangeInterval<T> {
         Self { reverse, start, end }
     }
 
+    /// Return the same range in the opposite direction. Unbounded range cannot be reversed and is returned unchanged.
+    pub fn reversed(self) -> Self {
+        let reverse = match (&self.start, &self.end) {
+            (Bound::Unbounded, _) | (_, Bound::Unbounded) => self.reverse,
+            _ => !self.reverse,
+        };
+        Self { reverse, ..self }
+    }
+
     /// Return the type of range
     pub fn range_type(&self) -> RangeType {
         match (self.reverse, &self.start, &self.end) {
@@ -627,6 +708,78 @@ mod tests {
         assert!(r!(!1..=1).is_empty());
     }
     
+    #[test]
+    fn test_macro() {
+        assert_eq!(r!(..), RangeInterval::<i32>::new(Bound::Unbounded, Bound::Unbounded));
+        assert_eq!(r!(..10), RangeInterval::new(Bound::Unbounded, Bound::Excluded(10)));
+        assert_eq!(r!(..!10), RangeInterval::new(Bound::Unbounded, Bound::Excluded(10)));
+        assert_eq!(r!(..=10), RangeInterval::new(Bound::Unbounded, Bound::Included(10)));
+        assert_eq!(r!(1..10), RangeInterval::new(Bound::Included(1), Bound::Excluded(10)));
+        assert_eq!(r!(1..!10), RangeInterval::new(Bound::Included(1), Bound::Excluded(10)));
+        assert_eq!(r!(1..=10), RangeInterval::new(Bound::Included(1), Bound::Included(10)));
+        assert_eq!(r!(1..), RangeInterval::new(Bound::Included(1), Bound::Unbounded));
+        assert_eq!(r!(!1..), RangeInterval::new(Bound::Excluded(1), Bound::Unbounded));
+        assert_eq!(r!(!1..10), RangeInterval::new(Bound::Excluded(1), Bound::Excluded(10)));
+        assert_eq!(r!(!1..!10), RangeInterval::new(Bound::Excluded(1), Bound::Excluded(10)));
+        assert_eq!(r!(!1..=10), RangeInterval::new(Bound::Excluded(1), Bound::Included(10)));
+        assert_eq!(r!(5), RangeInterval::new(Bound::Included(5), Bound::Included(5)));
+
+        assert_eq!(r!(-5..), RangeInterval::new(Bound::Included(-5), Bound::Unbounded));
+        assert_eq!(r!(!-5..-1), RangeInterval::new(Bound::Excluded(-5), Bound::Excluded(-1)));
+
+        let a = 3;
+        let b = 7;
+        assert_eq!(r!(a..b), r!(3..7));
+        assert_eq!(r!(a + 1..=b * 2), r!(4..=14));
+        assert_eq!(r!(!a..!b), r!(!3..7));
+        assert_eq!(r!(..=b - a), r!(..=4));
+        assert_eq!(r!(a.max(b)), r!(7..=7));
+        assert_eq!(r!(b..a), r!(7..3));
+
+        let rev = 2;
+        assert_eq!(r!(rev..b), r!(2..7));
+        assert_eq!(r!(rev..=b), r!(2..=7));
+        assert_eq!(r!(rev), r!(2..=2));
+        assert_eq!(r!(rev + 1..10), r!(3..10));
+        assert_eq!(r!(rev * b..-rev), r!(14..-2));
+        assert_eq!(r!(rev - 1), r!(1..=1));
+        assert_eq!(r!(rev: 1..10), r!(1..10).reversed());
+        assert_eq!(r!(rev: !a..=b), r!(!3..=7).reversed());
+        assert_eq!(r!(rev: 10..1), r!(!1..=10));
+        assert_eq!(r!(rev: rev..b), r!(2..7).reversed());
+    }
+
+    #[test]
+    fn test_reversed() {
+        assert_eq!(r!(1..10).reversed(), RangeInterval { reverse: true, start: Bound::Included(1), end: Bound::Excluded(10) });
+        assert_eq!(r!(10..1).reversed(), RangeInterval { reverse: false, start: Bound::Excluded(1), end: Bound::Included(10) });
+        assert_eq!(r!(1..10).reversed().reversed(), r!(1..10));
+        assert_eq!(r!(1..).reversed(), r!(1..));
+    }
+
+    #[test]
+    fn test_closed() {
+        assert_eq!(r!(1..10).closed(), Some(r!(1..=9)));
+        assert_eq!(r!(!1..10).closed(), Some(r!(2..=9)));
+        assert_eq!(r!(!1..=10).closed(), Some(r!(2..=10)));
+        assert_eq!(r!(!1..).closed(), Some(r!(2..)));
+        assert_eq!(r!(..10).closed(), Some(r!(..=9)));
+        assert_eq!(r!(10..1).closed(), Some(r!(10..=2)));
+        assert_eq!(r!(!1..2).closed(), None);
+        assert_eq!(r!(1..1).closed(), None);
+        assert_eq!(r!(!255u8..).closed(), None);
+    }
+
+    #[test]
+    fn test_half_open() {
+        assert_eq!(r!(1..10).half_open(), Some(r!(1..10)));
+        assert_eq!(r!(!1..=10).half_open(), Some(r!(2..11)));
+        assert_eq!(r!(..=10).half_open(), Some(r!(..11)));
+        assert_eq!(r!(!1..).half_open(), Some(r!(2..)));
+        assert_eq!(r!(250u8..=255).half_open(), Some(r!(250..=255)));
+        assert_eq!(r!(!1..2).half_open(), None);
+    }
+
     #[test]
     fn test_map() {
         assert_eq!(r!(1..10).map(|v| 1.0 + (*v as f64)), r!(2.0 .. 11.0));
@@ -0,0 +1,93 @@
+//! Relation of two ranges, comparing the bounds exactly, so `<1..3)` is less than `<3..5>`, while `<1..3>`
+//! overlaps with it in the point 3.
+//!
+//! `RangeOrdering` replaces the deprecated `intersect::IntersectionExt`, which is implemented only for some
+//! pairs of the std ranges and cannot express the excluded start. The variants keep their names,
+//! `IntersectionExt::is_any` becomes `RangeOrdering::is_overlapping`, and `Intersect::intersect_ext(&a, &b)`
+//! becomes `RangeInterval::from(a).relation(&RangeInterval::from(b))`.
+
+
+use std::cmp::Ordering;
+use crate::bounds::cmp_cut;
+use crate::range_interval::RangeInterval;
+
+/// The relation of the first range to the second range.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RangeOrdering {
+    /// One or both of the ranges have no value.
+    Empty,
+    /// The first range ends before the second range starts, or exactly where it starts.
+    Less,
+    /// The first range starts before the second range, and overlaps with it with the end.
+    LessOverlap,
+    /// The first range is contained within the second range.
+    Within,
+    /// The ranges have the same values.
+    Same,
+    /// The first range contains the second range.
+    Over,
+    /// The first range ends after the second range, and overlaps with it with the start.
+    GreaterOverlap,
+    /// The first range starts after the second range ends, or exactly where it ends.
+    Greater,
+}
+
+impl RangeOrdering {
+    /// Test if there is a value in both ranges.
+    pub fn is_overlapping(&self) -> bool {
+        !matches!(self, RangeOrdering::Empty | RangeOrdering::Less | RangeOrdering::Greater)
+    }
+
+    /// Test if the first range is contained within the second range, the same ranges are within as well.
+    pub fn is_within(&self) -> bool {
+        matches!(self, RangeOrdering::Within | RangeOrdering::Same)
+    }
+}
+
+impl<T: PartialOrd> RangeInterval<T> {
+    /// The relation of this range to the other range, the direction of the ranges is ignored.
+    pub fn relation(&self, other: &Self) -> RangeOrdering {
+        if self.is_void() || other.is_void() {
+            return RangeOrdering::Empty;
+        }
+        let (start, end) = (self.start_cut(), self.end_cut());
+        let (other_start, other_end) = (other.start_cut(), other.end_cut());
+        if cmp_cut(&end, &other_start) != Ordering::Greater {
+            return RangeOrdering::Less;
+        }
+        if cmp_cut(&start, &other_end) != Ordering::Less {
+            return RangeOrdering::Greater;
+        }
+        match (cmp_cut(&start, &other_start), cmp_cut(&end, &other_end)) {
+            (Ordering::Equal, Ordering::Equal) => RangeOrdering::Same,
+            (Ordering::Less, Ordering::Less) => RangeOrdering::LessOverlap,
+            (Ordering::Greater, Ordering::Greater) => RangeOrdering::GreaterOverlap,
+            (Ordering::Less, _) | (_, Ordering::Greater) => RangeOrdering::Over,
+            _ => RangeOrdering::Within,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_relation() {
+        assert_eq!(r!(1..3).relation(&r!(3..=5)), RangeOrdering::Less);
+        assert_eq!(r!(1..=3).relation(&r!(3..=5)), RangeOrdering::LessOverlap);
+        assert_eq!(r!(3..=5).relation(&r!(1..=3)), RangeOrdering::GreaterOverlap);
+        assert_eq!(r!(!3..=5).relation(&r!(1..=3)), RangeOrdering::Greater);
+        assert_eq!(r!(2..3).relation(&r!(1..=3)), RangeOrdering::Within);
+        assert_eq!(r!(1..=3).relation(&r!(1..3)), RangeOrdering::Over);
+        assert_eq!(r!(..).relation(&r!(1..3)), RangeOrdering::Over);
+        assert_eq!(r!(10..1).relation(&r!(!1..=10)), RangeOrdering::Same);
+        assert_eq!(r!(1..1).relation(&r!(..)), RangeOrdering::Empty);
+        assert!(r!(1..=3).relation(&r!(3..=5)).is_overlapping());
+        assert!(!r!(1..3).relation(&r!(3..=5)).is_overlapping());
+        assert!(r!(10..1).relation(&r!(1..=10)).is_within());
+        assert!(!r!(1..=3).relation(&r!(1..3)).is_within());
+    }
+}
@@ -0,0 +1,179 @@
+//! Interval scheduling over ranges: the maximal subset without overlapping, the subset with maximal weight,
+//! and the assignment of the ranges to the minimal number of resources. The bounds are compared exactly,
+//! so `<1..3)` and `<3..5>` can be scheduled after each other, while `<1..3>` and `<3..5>` can not.
+//! The ranges without any value do not occupy anything, they are never selected and always get the resource 0.
+
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::ops::Add;
+use crate::arithmetic::Arithmetic;
+use crate::bounds::{cmp_cut, Cut};
+use crate::range_interval::RangeInterval;
+
+/// The indices of the non empty ranges, sorted by their end.
+fn by_end<T: PartialOrd>(ranges: &[RangeInterval<T>]) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..ranges.len()).filter(|&i| !ranges[i].is_void()).collect();
+    indices.sort_by(|&a, &b| cmp_cut(&ranges[a].end_cut(), &ranges[b].end_cut()));
+    indices
+}
+
+/// The maximal number of ranges without overlapping, found greedily by the earliest end.
+/// Returns the sorted indices of the selected ranges.
+pub fn max_non_overlapping<T: PartialOrd>(ranges: &[RangeInterval<T>]) -> Vec<usize> {
+    let mut selected: Vec<usize> = Vec::new();
+    for i in by_end(ranges) {
+        match selected.last() {
+            Some(&last) if ranges[last].overlaps(&ranges[i]) => {}
+            _ => selected.push(i),
+        }
+    }
+    selected.sort_unstable();
+    selected
+}
+
+/// The ranges without overlapping with the maximal sum of the weights, found by dynamic programming in O(n log n).
+/// Returns the sum and the sorted indices of the selected ranges. The ranges with negative weight are never selected.
+pub fn max_weight_schedule<T, W>(ranges: &[(RangeInterval<T>, W)]) -> (W, Vec<usize>)
+    where
+        T: PartialOrd,
+        W: Arithmetic + Add<Output = W> + PartialOrd + Clone,
+{
+    let intervals: Vec<RangeInterval<&T>> = ranges.iter()
+        .map(|(range, _)| RangeInterval { reverse: false, start: range.start.as_ref(), end: range.end.as_ref() })
+        .collect();
+    let order = by_end(&intervals);
+    // best[j] is the maximal weight using the first j ranges of the order, take[j] marks if the range j - 1 was used
+    let mut best: Vec<W> = vec![W::zero()];
+    let mut take: Vec<bool> = vec![false];
+    let mut previous: Vec<usize> = vec![0];
+    for (j, &i) in order.iter().enumerate() {
+        let start = intervals[i].start_cut();
+        let p = order[..j].partition_point(|&k| cmp_cut(&intervals[k].end_cut(), &start) != Ordering::Greater);
+        let with = ranges[i].1.clone() + best[p].clone();
+        if with > best[j] {
+            best.push(with);
+            take.push(true);
+        } else {
+            best.push(best[j].clone());
+            take.push(false);
+        }
+        previous.push(p);
+    }
+
+    let mut selected = Vec::new();
+    let mut j = order.len();
+    while j > 0 {
+        if take[j] {
+            selected.push(order[j - 1]);
+            j = previous[j];
+        } else {
+            j -= 1;
+        }
+    }
+    selected.sort_unstable();
+    (best[order.len()].clone(), selected)
+}
+
+/// The minimal number of resources needed to use all the ranges, so that no resource is used by overlapping ranges.
+pub fn min_resources<T: PartialOrd>(ranges: &[RangeInterval<T>]) -> usize {
+    crate::sweep::max_depth(ranges)
+}
+
+/// The range in use, ordered by the end from the latest, so the heap of them gives the earliest end first.
+struct Active<'a, T> {
+    end: Cut<&'a T>,
+    index: usize,
+}
+
+impl<T: PartialOrd> PartialEq for Active<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PartialOrd> Eq for Active<'_, T> {}
+
+impl<T: PartialOrd> PartialOrd for Active<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd> Ord for Active<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_cut(&other.end, &self.end)
+    }
+}
+
+/// Assign each range to a resource, so that the ranges of any resource do not overlap, using the minimal number
+/// of resources. Returns the resource of each range, the resources are numbered from 0, and the lowest free
+/// resource is used first.
+pub fn assign_resources<T: PartialOrd>(ranges: &[RangeInterval<T>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..ranges.len()).filter(|&i| !ranges[i].is_void()).collect();
+    order.sort_by(|&a, &b| cmp_cut(&ranges[a].start_cut(), &ranges[b].start_cut()));
+
+    let mut assignment = vec![0; ranges.len()];
+    let mut active: BinaryHeap<Active<T>> = BinaryHeap::new();
+    let mut free: BinaryHeap<Reverse<usize>> = BinaryHeap::new();
+    let mut count = 0;
+    for i in order {
+        let start = ranges[i].start_cut();
+        while let Some(earliest) = active.peek() {
+            if cmp_cut(&earliest.end, &start) == Ordering::Greater {
+                break;
+            }
+            free.push(Reverse(assignment[earliest.index]));
+            active.pop();
+        }
+        assignment[i] = match free.pop() {
+            Some(Reverse(resource)) => resource,
+            None => {
+                count += 1;
+                count - 1
+            }
+        };
+        active.push(Active { end: ranges[i].end_cut(), index: i });
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_max_non_overlapping() {
+        assert_eq!(max_non_overlapping::<i32>(&[]), vec![]);
+        assert_eq!(max_non_overlapping(&[r!(1..3), r!(3..=5)]), vec![0, 1]);
+        assert_eq!(max_non_overlapping(&[r!(1..=3), r!(3..=5)]), vec![0]);
+        assert_eq!(max_non_overlapping(&[r!(1..=3), r!(!3..=5)]), vec![0, 1]);
+        assert_eq!(max_non_overlapping(&[r!(0..10), r!(1..3), r!(3..5), r!(4..6), r!(6..9), r!(2..2)]), vec![1, 2, 4]);
+        assert_eq!(max_non_overlapping(&[r!(5..0), r!(..1), r!(5..)]), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_max_weight_schedule() {
+        assert_eq!(max_weight_schedule::<i32, i32>(&[]), (0, vec![]));
+        let ranges = [(r!(0..10), 10), (r!(1..3), 3), (r!(3..5), 3), (r!(4..6), 5), (r!(6..9), 3)];
+        assert_eq!(max_weight_schedule(&ranges), (11, vec![1, 3, 4]));
+        let ranges = [(r!(0..10), 12), (r!(1..3), 3), (r!(3..5), 3), (r!(4..6), 5), (r!(6..9), 3)];
+        assert_eq!(max_weight_schedule(&ranges), (12, vec![0]));
+        assert_eq!(max_weight_schedule(&[(r!(1..=3), 2.0), (r!(3..=5), 2.5)]), (2.5, vec![1]));
+        assert_eq!(max_weight_schedule(&[(r!(1..3), 2.0), (r!(3..=5), 2.5)]), (4.5, vec![0, 1]));
+        assert_eq!(max_weight_schedule(&[(r!(1..3), -2), (r!(3..=5), 0)]), (0, vec![]));
+    }
+
+    #[test]
+    fn test_resources() {
+        let ranges = [r!(0..10), r!(1..3), r!(3..5), r!(4..6), r!(6..9), r!(2..2), r!(9..=12)];
+        assert_eq!(min_resources(&ranges), 3);
+        assert_eq!(assign_resources(&ranges), vec![0, 1, 1, 2, 1, 0, 1]);
+        assert_eq!(min_resources(&[r!(1..=3), r!(3..=5)]), 2);
+        assert_eq!(assign_resources(&[r!(1..=3), r!(3..=5)]), vec![0, 1]);
+        assert_eq!(assign_resources(&[r!(1..3), r!(3..=5)]), vec![0, 0]);
+        assert_eq!(assign_resources(&[r!(..), r!(..0), r!(!0..), r!(0)]), vec![0, 1, 1, 1]);
+    }
+}
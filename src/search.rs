@@ -0,0 +1,112 @@
+//! Binary search of the values in a range in sorted slices, or in slices sorted by a key like the maps
+//! kept as sorted `(key, value)` pairs. The search takes logarithmic time with `partition_point`.
+
+
+use std::ops::{Bound, Range};
+use crate::range_interval::RangeInterval;
+
+/// The indices of the elements in the range, in the slice sorted by the comparisons of the elements
+/// to the bounds, `lt` being the less than and `le` the less or equal comparison.
+fn indices_by<T, K>(
+    sorted: &[T],
+    range: &RangeInterval<K>,
+    lt: impl Fn(&T, &K) -> bool,
+    le: impl Fn(&T, &K) -> bool,
+) -> Range<usize> {
+    let start = sorted.partition_point(|element| match &range.start {
+        Bound::Included(start) => lt(element, start),
+        Bound::Excluded(start) => le(element, start),
+        Bound::Unbounded => false,
+    });
+    let end = sorted.partition_point(|element| match &range.end {
+        Bound::Included(end) => le(element, end),
+        Bound::Excluded(end) => lt(element, end),
+        Bound::Unbounded => true,
+    });
+    // the void range like `<5..5)` ends before it starts
+    start..end.max(start)
+}
+
+fn indices<T: PartialOrd>(sorted: &[T], range: &RangeInterval<T>) -> Range<usize> {
+    indices_by(sorted, range, |element, bound| element < bound, |element, bound| element <= bound)
+}
+
+fn indices_by_key<T, K: PartialOrd>(sorted: &[T], range: &RangeInterval<K>, key: impl Fn(&T) -> K) -> Range<usize> {
+    indices_by(sorted, range, |element, bound| key(element) < *bound, |element, bound| key(element) <= *bound)
+}
+
+/// The elements of the sorted slice which are in the range.
+pub fn slice_within<'a, T: PartialOrd>(sorted: &'a [T], range: &RangeInterval<T>) -> &'a [T] {
+    &sorted[indices(sorted, range)]
+}
+
+/// The elements of the slice sorted by the key, with the keys in the range.
+pub fn slice_within_by_key<'a, T, K: PartialOrd>(sorted: &'a [T], range: &RangeInterval<K>, key: impl Fn(&T) -> K) -> &'a [T] {
+    &sorted[indices_by_key(sorted, range, key)]
+}
+
+/// The number of the elements of the sorted slice which are in the range.
+pub fn count_within<T: PartialOrd>(sorted: &[T], range: &RangeInterval<T>) -> usize {
+    indices(sorted, range).len()
+}
+
+/// The number of the elements of the slice sorted by the key, with the keys in the range.
+pub fn count_within_by_key<T, K: PartialOrd>(sorted: &[T], range: &RangeInterval<K>, key: impl Fn(&T) -> K) -> usize {
+    indices_by_key(sorted, range, key).len()
+}
+
+/// The elements of the sorted slice below, in and above the range.
+pub fn split_within<'a, T: PartialOrd>(sorted: &'a [T], range: &RangeInterval<T>) -> (&'a [T], &'a [T], &'a [T]) {
+    split_at(sorted, indices(sorted, range))
+}
+
+/// The elements of the slice sorted by the key, with the keys below, in and above the range.
+pub fn split_within_by_key<'a, T, K: PartialOrd>(
+    sorted: &'a [T],
+    range: &RangeInterval<K>,
+    key: impl Fn(&T) -> K,
+) -> (&'a [T], &'a [T], &'a [T]) {
+    split_at(sorted, indices_by_key(sorted, range, key))
+}
+
+fn split_at<T>(sorted: &[T], indices: Range<usize>) -> (&[T], &[T], &[T]) {
+    let (below, rest) = sorted.split_at(indices.start);
+    let (inside, above) = rest.split_at(indices.len());
+    (below, inside, above)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_slice_within() {
+        let sorted = [1, 3, 3, 5, 7, 9];
+        assert_eq!(slice_within(&sorted, &r!(3..7)), &[3, 3, 5]);
+        assert_eq!(slice_within(&sorted, &r!(!3..=7)), &[5, 7]);
+        assert_eq!(slice_within(&sorted, &r!(..=3)), &[1, 3, 3]);
+        assert_eq!(slice_within(&sorted, &r!(!7..)), &[9]);
+        assert_eq!(slice_within(&sorted, &r!(..)), &sorted);
+        assert_eq!(slice_within(&sorted, &r!(7..3)), &[5, 7]);
+        assert_eq!(slice_within(&sorted, &r!(4..=4)), &[] as &[i32]);
+        assert_eq!(slice_within(&sorted, &r!(!3..3)), &[] as &[i32]);
+        assert_eq!(slice_within(&[] as &[i32], &r!(..)), &[] as &[i32]);
+        assert_eq!(count_within(&sorted, &r!(3..=3)), 2);
+        assert_eq!(count_within(&sorted, &r!(10..)), 0);
+    }
+
+    #[test]
+    fn test_by_key() {
+        let pairs = [(1, "a"), (4, "b"), (6, "c"), (8, "d")];
+        assert_eq!(slice_within_by_key(&pairs, &r!(!4..8), |&(k, _)| k), &[(6, "c")]);
+        assert_eq!(count_within_by_key(&pairs, &r!(4..=8), |&(k, _)| k), 3);
+        let (below, inside, above) = split_within_by_key(&pairs, &r!(2..7), |&(k, _)| k);
+        assert_eq!(below, &[(1, "a")]);
+        assert_eq!(inside, &[(4, "b"), (6, "c")]);
+        assert_eq!(above, &[(8, "d")]);
+        let (below, inside, above) = split_within(&[1, 2, 3], &r!(!5..5));
+        assert_eq!((below, inside, above), (&[1, 2, 3][..], &[][..], &[][..]));
+    }
+}
@@ -0,0 +1,248 @@
+//! Segment tree over the slots `0..len`, with updates of all the slots in a range applied lazily, and aggregation
+//! of the slots in a range. Both take a `RangeInterval<usize>`, the parts of the range outside of the slots are
+//! ignored, and the reversed range covers the same slots as the forward one.
+
+
+use std::ops::Bound;
+use crate::range_interval::RangeInterval;
+
+/// The aggregation and the update of the slots of the segment tree.
+pub trait SegmentOps {
+    /// The value of a slot, and the aggregated value of a range of slots.
+    type Value: Clone;
+    /// The update applied to all the slots of a range.
+    type Update: Clone;
+
+    /// The aggregated value of no slots, neutral to `combine`.
+    fn identity() -> Self::Value;
+
+    /// Aggregate the value of the slots on the left with the value of the slots on the right.
+    fn combine(left: &Self::Value, right: &Self::Value) -> Self::Value;
+
+    /// Apply the update to the aggregated value of the number of slots.
+    fn apply(value: &Self::Value, update: &Self::Update, len: usize) -> Self::Value;
+
+    /// The update doing the older update and then the newer one.
+    fn compose(older: &Self::Update, newer: &Self::Update) -> Self::Update;
+}
+
+/// Segment tree with lazy range updates.
+pub struct SegmentTree<O: SegmentOps> {
+    len: usize,
+    values: Vec<O::Value>,
+    pending: Vec<Option<O::Update>>,
+}
+
+impl<O: SegmentOps> Clone for SegmentTree<O> {
+    fn clone(&self) -> Self {
+        Self { len: self.len, values: self.values.clone(), pending: self.pending.clone() }
+    }
+}
+
+impl<O: SegmentOps> SegmentTree<O> {
+    /// Create the tree with the same value in all the slots.
+    pub fn new(len: usize, value: O::Value) -> Self {
+        Self::from_values(vec![value; len])
+    }
+
+    /// Create the tree with the values of the slots.
+    pub fn from_values(values: Vec<O::Value>) -> Self {
+        let len = values.len();
+        let size = 4 * len.max(1);
+        let mut tree = Self { len, values: vec![O::identity(); size], pending: vec![None; size] };
+        if len > 0 {
+            tree.build(1, 0, len, &values);
+        }
+        tree
+    }
+
+    fn build(&mut self, node: usize, low: usize, high: usize, values: &[O::Value]) {
+        if high - low == 1 {
+            self.values[node] = values[low].clone();
+        } else {
+            let middle = (low + high) / 2;
+            self.build(2 * node, low, middle, values);
+            self.build(2 * node + 1, middle, high, values);
+            self.values[node] = O::combine(&self.values[2 * node], &self.values[2 * node + 1]);
+        }
+    }
+
+    /// The number of slots.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Test if there are no slots.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The slots of the range, limited to the slots of the tree.
+    fn slots(&self, range: &RangeInterval<usize>) -> (usize, usize) {
+        let offsets = range.offsets(self.len);
+        (offsets.start.min(self.len), offsets.end.min(self.len))
+    }
+
+    fn push_update(&mut self, node: usize, update: &O::Update, len: usize) {
+        self.values[node] = O::apply(&self.values[node], update, len);
+        self.pending[node] = Some(match &self.pending[node] {
+            Some(older) => O::compose(older, update),
+            None => update.clone(),
+        });
+    }
+
+    fn push_down(&mut self, node: usize, low: usize, middle: usize, high: usize) {
+        if let Some(update) = self.pending[node].take() {
+            self.push_update(2 * node, &update, middle - low);
+            self.push_update(2 * node + 1, &update, high - middle);
+        }
+    }
+
+    /// Apply the update to all the slots in the range.
+    pub fn update(&mut self, range: RangeInterval<usize>, update: O::Update) {
+        let (start, end) = self.slots(&range);
+        if start < end {
+            self.update_node(1, 0, self.len, start, end, &update);
+        }
+    }
+
+    fn update_node(&mut self, node: usize, low: usize, high: usize, start: usize, end: usize, update: &O::Update) {
+        if end <= low || high <= start {
+            return;
+        }
+        if start <= low && high <= end {
+            self.push_update(node, update, high - low);
+            return;
+        }
+        let middle = (low + high) / 2;
+        self.push_down(node, low, middle, high);
+        self.update_node(2 * node, low, middle, start, end, update);
+        self.update_node(2 * node + 1, middle, high, start, end, update);
+        self.values[node] = O::combine(&self.values[2 * node], &self.values[2 * node + 1]);
+    }
+
+    /// The aggregated value of the slots in the range, from the lowest slot, the identity if there is no slot.
+    pub fn query(&mut self, range: RangeInterval<usize>) -> O::Value {
+        let (start, end) = self.slots(&range);
+        if start < end {
+            self.query_node(1, 0, self.len, start, end)
+        } else {
+            O::identity()
+        }
+    }
+
+    fn query_node(&mut self, node: usize, low: usize, high: usize, start: usize, end: usize) -> O::Value {
+        if end <= low || high <= start {
+            return O::identity();
+        }
+        if start <= low && high <= end {
+            return self.values[node].clone();
+        }
+        let middle = (low + high) / 2;
+        self.push_down(node, low, middle, high);
+        let left = self.query_node(2 * node, low, middle, start, end);
+        let right = self.query_node(2 * node + 1, middle, high, start, end);
+        O::combine(&left, &right)
+    }
+
+    /// The value of the slot, None if there is no such slot.
+    pub fn get(&mut self, index: usize) -> Option<O::Value> {
+        if index < self.len {
+            Some(self.query(RangeInterval::new(Bound::Included(index), Bound::Included(index))))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    /// Sum of the slots with adding to every slot.
+    struct SumAdd;
+
+    impl SegmentOps for SumAdd {
+        type Value = i64;
+        type Update = i64;
+
+        fn identity() -> i64 {
+            0
+        }
+        fn combine(left: &i64, right: &i64) -> i64 {
+            left + right
+        }
+        fn apply(value: &i64, update: &i64, len: usize) -> i64 {
+            value + update * len as i64
+        }
+        fn compose(older: &i64, newer: &i64) -> i64 {
+            older + newer
+        }
+    }
+
+    /// Maximum of the slots with adding to every slot.
+    struct MaxAdd;
+
+    impl SegmentOps for MaxAdd {
+        type Value = i64;
+        type Update = i64;
+
+        fn identity() -> i64 {
+            i64::MIN
+        }
+        fn combine(left: &i64, right: &i64) -> i64 {
+            *left.max(right)
+        }
+        fn apply(value: &i64, update: &i64, _: usize) -> i64 {
+            value.saturating_add(*update)
+        }
+        fn compose(older: &i64, newer: &i64) -> i64 {
+            older + newer
+        }
+    }
+
+    #[test]
+    fn test_sum() {
+        let mut tree = SegmentTree::<SumAdd>::new(10, 0);
+        assert_eq!(tree.len(), 10);
+        tree.update(r!(2..5), 3);
+        assert_eq!(tree.query(r!(..)), 9);
+        tree.update(r!(!3..=6), 1);
+        assert_eq!(tree.query(r!(..)), 12);
+        assert_eq!(tree.query(r!(0..3)), 3);
+        assert_eq!(tree.query(r!(4..=4)), 4);
+        assert_eq!(tree.query(r!(7..3)), 4 + 1 + 1);
+        assert_eq!(tree.query(r!(8..)), 0);
+        assert_eq!(tree.query(r!(5..5)), 0);
+        tree.update(r!(8..100), 10);
+        assert_eq!(tree.query(r!(5..)), 1 + 1 + 20);
+        assert_eq!(tree.get(9), Some(10));
+        assert_eq!(tree.get(10), None);
+
+        let values: Vec<i64> = (0..10).collect();
+        let mut tree = SegmentTree::<SumAdd>::from_values(values);
+        assert_eq!(tree.query(r!(..)), 45);
+        assert_eq!(tree.query(r!(3..=5)), 12);
+    }
+
+    #[test]
+    fn test_max() {
+        let mut tree = SegmentTree::<MaxAdd>::from_values(vec![5, 1, 4, 2, 8, 0, 3]);
+        assert_eq!(tree.query(r!(..)), 8);
+        assert_eq!(tree.query(r!(..4)), 5);
+        tree.update(r!(1..=3), 10);
+        assert_eq!(tree.query(r!(..4)), 14);
+        assert_eq!(tree.query(r!(!3..)), 8);
+        tree.update(r!(6..0), -3);
+        assert_eq!(tree.query(r!(1..=6)), 11);
+        assert_eq!(tree.get(0), Some(5));
+        assert_eq!(tree.query(r!(7..)), i64::MIN);
+
+        let mut tree = SegmentTree::<MaxAdd>::new(0, 0);
+        assert!(tree.is_empty());
+        tree.update(r!(..), 1);
+        assert_eq!(tree.query(r!(..)), i64::MIN);
+    }
+}
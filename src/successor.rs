@@ -5,6 +5,15 @@ pub trait Successor: Sized {
 
     /// The previous value from this value
     fn prev(&self) -> Option<Self>;
+
+    /// The value after the number of steps from this value
+    fn advance(&self, steps: usize) -> Option<Self> where Self: Clone {
+        let mut value = self.clone();
+        for _ in 0..steps {
+            value = value.next()?;
+        }
+        Some(value)
+    }
 }
 
 
@@ -17,6 +26,10 @@ macro_rules! impl_int {
             fn prev(&self) -> Option<Self> {
                 self.checked_sub(1)
             }
+            fn advance(&self, steps: usize) -> Option<Self> {
+                let value = (*self as i128).checked_add(std::convert::TryFrom::try_from(steps).ok()?)?;
+                std::convert::TryFrom::try_from(value).ok()
+            }
         }
     }
 }
@@ -31,3 +44,32 @@ impl_int!(u16);
 impl_int!(u32);
 impl_int!(u64);
 impl_int!(usize);
+
+/// The byte strings are ordered lexicographically, the next key of `b"ab"` is `b"ab\0"`. The previous key
+/// exists only for the keys ending with 0, any other key has infinitely many keys just below it.
+impl Successor for Vec<u8> {
+    fn next(&self) -> Option<Self> {
+        let mut next = self.clone();
+        next.push(0);
+        Some(next)
+    }
+    fn prev(&self) -> Option<Self> {
+        match self.split_last() {
+            Some((0, rest)) => Some(rest.to_vec()),
+            _ => None,
+        }
+    }
+}
+
+/// The strings are ordered lexicographically, the next key of `"ab"` is `"ab\0"`. The previous key exists
+/// only for the keys ending with `'\0'`.
+impl Successor for String {
+    fn next(&self) -> Option<Self> {
+        let mut next = self.clone();
+        next.push('\0');
+        Some(next)
+    }
+    fn prev(&self) -> Option<Self> {
+        self.strip_suffix('\0').map(String::from)
+    }
+}
@@ -0,0 +1,120 @@
+//! Sweep-line analytics over collections of ranges: the depth of overlapping, the parts covered by at least k ranges,
+//! and the depth as a step function. The bounds are compared exactly, so `<1..3)` and `<3..5>` do not overlap,
+//! while `<1..3>` and `<3..5>` overlap in the point 3.
+
+
+use std::cmp::Ordering;
+use crate::bounds::{cmp_cut, max_cut, Cut};
+use crate::normalize::normalize;
+use crate::range_interval::RangeInterval;
+
+/// The positions where the depth changes, sorted, with the depth after each position.
+fn steps<T: PartialOrd>(ranges: &[RangeInterval<T>]) -> Vec<(Cut<&T>, usize)> {
+    let mut events: Vec<(Cut<&T>, isize)> = ranges.iter()
+        .filter(|range| !range.is_void())
+        .flat_map(|range| vec![(range.start_cut(), 1), (range.end_cut(), -1)])
+        .collect();
+    events.sort_by(|a, b| cmp_cut(&a.0, &b.0));
+    let mut steps: Vec<(Cut<&T>, usize)> = Vec::new();
+    let mut depth: isize = 0;
+    for (cut, delta) in events {
+        depth += delta;
+        match steps.last_mut() {
+            Some(last) if cmp_cut(&last.0, &cut) == Ordering::Equal => last.1 = depth as usize,
+            _ => steps.push((cut, depth as usize)),
+        }
+    }
+    steps
+}
+
+/// The maximal number of ranges which contain the same value.
+pub fn max_depth<T: PartialOrd>(ranges: &[RangeInterval<T>]) -> usize {
+    steps(ranges).into_iter().map(|(_, depth)| depth).max().unwrap_or(0)
+}
+
+/// The depth as a step function, the consecutive parts from the start of the first range to the end of the last range,
+/// each with the number of ranges which contain it. The parts not covered by any range have the depth 0.
+pub fn depth_profile<T: PartialOrd + Clone>(ranges: &[RangeInterval<T>]) -> Vec<(RangeInterval<T>, usize)> {
+    steps(ranges).windows(2)
+        .map(|step| {
+            let (start, depth) = step[0];
+            let (end, _) = step[1];
+            (RangeInterval { reverse: false, start: start.to_start(), end: end.to_end() }, depth)
+        })
+        .collect()
+}
+
+/// The sorted disjoint parts which are contained in at least k ranges.
+pub fn depth_at_least<T: PartialOrd + Clone>(ranges: &[RangeInterval<T>], k: usize) -> Vec<RangeInterval<T>> {
+    normalize(depth_profile(ranges).into_iter()
+        .filter(|(_, depth)| *depth >= k.max(1))
+        .map(|(range, _)| range))
+}
+
+/// Merge the ranges which overlap, in O(n log n). The ranges which only touch each other, like `<1..3)` and `<3..5>`,
+/// are not merged, use `normalize` to merge them as well. The result is sorted, without empty ranges.
+pub fn merge_overlapping<T: PartialOrd + Clone>(ranges: &[RangeInterval<T>]) -> Vec<RangeInterval<T>> {
+    let mut ranges: Vec<_> = ranges.iter()
+        .filter(|range| !range.is_void())
+        .map(|range| RangeInterval { reverse: false, ..range.clone() })
+        .collect();
+    ranges.sort_by(|a, b| a.cmp_bounds(b));
+    let mut result: Vec<RangeInterval<T>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match result.last_mut() {
+            Some(last) if last.overlaps(&range) => {
+                last.end = max_cut(last.end_cut(), range.end_cut()).to_end();
+            }
+            _ => result.push(range),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_max_depth() {
+        assert_eq!(max_depth::<i32>(&[]), 0);
+        assert_eq!(max_depth(&[r!(1..3), r!(3..=5)]), 1);
+        assert_eq!(max_depth(&[r!(1..=3), r!(3..=5)]), 2);
+        assert_eq!(max_depth(&[r!(1..=3), r!(!3..=5)]), 1);
+        assert_eq!(max_depth(&[r!(1..10), r!(2..4), r!(3..5), r!(4..6)]), 3);
+        assert_eq!(max_depth(&[r!(1..10), r!(2..4), r!(!3..5), r!(4..6)]), 3);
+        assert_eq!(max_depth(&[r!(..), r!(..0), r!(!0..), r!(0)]), 2);
+        assert_eq!(max_depth(&[r!(1..1), r!(1..1)]), 0);
+        assert_eq!(max_depth(&[r!(10..1), r!(5..=5)]), 2);
+    }
+
+    #[test]
+    fn test_depth_profile() {
+        assert_eq!(depth_profile(&[r!(1..3), r!(3..=5)]), vec![(r!(1..3), 1), (r!(3..=5), 1)]);
+        assert_eq!(depth_profile(&[r!(1..=3), r!(3..=5)]), vec![(r!(1..3), 1), (r!(3..=3), 2), (r!(!3..=5), 1)]);
+        assert_eq!(depth_profile(&[r!(1..3), r!(!3..=5)]), vec![(r!(1..3), 1), (r!(3..=3), 0), (r!(!3..=5), 1)]);
+        assert_eq!(depth_profile(&[r!(..5), r!(2..)]), vec![(r!(..2), 1), (r!(2..5), 2), (r!(5..), 1)]);
+        assert_eq!(depth_profile(&[r!(1..10), r!(1..10)]), vec![(r!(1..10), 2)]);
+    }
+
+    #[test]
+    fn test_depth_at_least() {
+        let ranges = [r!(1..10), r!(2..4), r!(3..5), r!(4..6), r!(8..=12)];
+        assert_eq!(depth_at_least(&ranges, 1), vec![r!(1..=12)]);
+        assert_eq!(depth_at_least(&ranges, 2), vec![r!(2..6), r!(8..10)]);
+        assert_eq!(depth_at_least(&ranges, 3), vec![r!(3..5)]);
+        assert_eq!(depth_at_least(&ranges, 4), vec![]);
+        assert_eq!(depth_at_least(&[r!(1..=3), r!(3..=5)], 2), vec![r!(3..=3)]);
+        assert_eq!(depth_at_least(&[r!(1..3), r!(3..=5)], 2), vec![]);
+    }
+
+    #[test]
+    fn test_merge_overlapping() {
+        assert_eq!(merge_overlapping(&[r!(1..3), r!(3..=5)]), vec![r!(1..3), r!(3..=5)]);
+        assert_eq!(merge_overlapping(&[r!(3..=5), r!(1..=3)]), vec![r!(1..=5)]);
+        assert_eq!(merge_overlapping(&[r!(1..10), r!(12..15), r!(2..4), r!(!14..20)]), vec![r!(1..10), r!(12..20)]);
+        assert_eq!(merge_overlapping(&[r!(5..1), r!(..2), r!(7..7)]), vec![r!(..=5)]);
+    }
+}
@@ -0,0 +1,20 @@
+//! Helpers shared by the tests of the modules.
+
+
+use std::fmt::Formatter;
+use crate::range_interval::DisplayExt;
+
+/// Integer displayed by the ranges, to test the formatting.
+pub(crate) struct D(pub(crate) i32);
+
+impl From<i32> for D {
+    fn from(v: i32) -> Self {
+        D(v)
+    }
+}
+
+impl DisplayExt for D {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,118 @@
+//! Transformations of ranges which move, stretch or flip both ends, keeping the start less or equal than the end.
+
+
+use std::ops::{Add, Bound, Mul};
+use crate::arithmetic::Arithmetic;
+use crate::range_interval::RangeInterval;
+use crate::successor::Successor;
+
+/// The direction of a monotonic function.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Direction {
+    /// The function preserves the order of values.
+    Increasing,
+    /// The function reverses the order of values.
+    Decreasing,
+}
+
+impl<T> RangeInterval<T> {
+    /// Apply monotonic function to both ends of the range. If the function is decreasing, the ends are swapped,
+    /// so the start stays less or equal than the end, and the direction of the range is flipped.
+    pub fn map_monotonic<R, F: Fn(&T) -> R>(&self, f: F, direction: Direction) -> RangeInterval<R> {
+        let mapped = self.map(f);
+        match direction {
+            Direction::Increasing => mapped,
+            Direction::Decreasing => {
+                let reverse = match (&mapped.start, &mapped.end) {
+                    (Bound::Unbounded, _) | (_, Bound::Unbounded) => mapped.reverse,
+                    _ => !mapped.reverse,
+                };
+                RangeInterval { reverse, start: mapped.end, end: mapped.start }
+            }
+        }
+    }
+}
+
+impl<T: Add<Output = T> + Clone> RangeInterval<T> {
+    /// Move both ends of the range by the delta.
+    pub fn shift(&self, delta: T) -> Self {
+        self.map_monotonic(|v| v.clone() + delta.clone(), Direction::Increasing)
+    }
+}
+
+impl<T: Arithmetic + PartialOrd + Mul<Output = T> + Clone> RangeInterval<T> {
+    /// Multiply both ends of the range by the factor, negative factor flips the range.
+    /// The zero factor maps all the values to the point `<0..=0>`.
+    pub fn scale(&self, factor: T) -> Self {
+        if factor == T::zero() && !self.is_void() {
+            return RangeInterval { reverse: false, start: Bound::Included(T::zero()), end: Bound::Included(T::zero()) };
+        }
+        let direction = if factor < T::zero() { Direction::Decreasing } else { Direction::Increasing };
+        self.map_monotonic(|v| v.clone() * factor.clone(), direction)
+    }
+}
+
+impl<T: PartialOrd + Successor + Clone> RangeInterval<T> {
+    /// Return the value if it is contained in the range, otherwise the nearest value which is contained.
+    /// Excluded bounds are resolved with the next or previous value.
+    /// Return None if there is no value in the range.
+    pub fn clamp(&self, value: T) -> Option<T> {
+        let closed = self.clone().closed()?;
+        match (closed.start, closed.end) {
+            (Bound::Included(start), _) if value < start => Some(start),
+            (_, Bound::Included(end)) if value > end => Some(end),
+            _ => Some(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::r;
+
+    #[test]
+    fn test_shift() {
+        assert_eq!(r!(1..10).shift(5), r!(6..15));
+        assert_eq!(r!(!1..=10).shift(-5), r!(!-4..=5));
+        assert_eq!(r!(10..1).shift(5), r!(15..6));
+        assert_eq!(r!(..10).shift(5), r!(..15));
+        assert_eq!(r!(1.5..).shift(0.5), r!(2.0..));
+    }
+
+    #[test]
+    fn test_scale() {
+        assert_eq!(r!(1..10).scale(2), r!(2..20));
+        assert_eq!(r!(1..10).scale(-2), RangeInterval { reverse: true, start: Bound::Excluded(-20), end: Bound::Included(-2) });
+        assert_eq!(r!(10..1).scale(-2), r!(-20..-2));
+        assert_eq!(r!(!1..).scale(-2), r!(..-2));
+        assert_eq!(r!(..=3).scale(-1), r!(-3..));
+        assert_eq!(r!(1.0..=2.0).scale(0.5), r!(0.5..=1.0));
+        assert_eq!(r!(1..10).scale(0), r!(0..=0));
+        assert_eq!(r!(10..1).scale(0), r!(0..=0));
+        assert_eq!(r!(!1..).scale(0), r!(0..=0));
+        assert!(r!(5..5).scale(0).is_void());
+    }
+
+    #[test]
+    fn test_map_monotonic() {
+        assert_eq!(r!(1..10).map_monotonic(|v| v * 10, Direction::Increasing), r!(10..100));
+        assert_eq!(r!(1..10).map_monotonic(|v| 100 - v, Direction::Decreasing), r!(!90..=99).reversed());
+        assert_eq!(r!(!1..=10).map_monotonic(|v| 1.0 / (*v as f64), Direction::Decreasing), r!(0.1..1.0).reversed());
+        assert_eq!(r!(1..).map_monotonic(|v| -v, Direction::Decreasing), r!(..=-1));
+    }
+
+    #[test]
+    fn test_clamp() {
+        assert_eq!(r!(1..10).clamp(5), Some(5));
+        assert_eq!(r!(1..10).clamp(-5), Some(1));
+        assert_eq!(r!(1..10).clamp(15), Some(9));
+        assert_eq!(r!(1..=10).clamp(15), Some(10));
+        assert_eq!(r!(!1..10).clamp(1), Some(2));
+        assert_eq!(r!(10..1).clamp(0), Some(2));
+        assert_eq!(r!(..10).clamp(-50), Some(-50));
+        assert_eq!(r!(!3..).clamp(-50), Some(4));
+        assert_eq!(r!(!1..2).clamp(5), None);
+    }
+}